@@ -0,0 +1,76 @@
+use std::{hint::black_box, time::Instant};
+
+use aoc::{
+    bench::{format_duration, Stats},
+    geometry::Point2,
+    grid::Grid,
+    search::{astar, bfs, dijkstra},
+};
+
+const SIZE: usize = 300;
+const RUNS: u32 = 10;
+
+/// A `SIZE` square of weights 1 to 9 from a fixed linear congruential sequence.
+fn weights() -> Grid<u32> {
+    let mut grid = Grid::new(SIZE, SIZE, 0);
+    let mut seed = 0x2023_u64;
+    for y in 0..SIZE as i64 {
+        for x in 0..SIZE as i64 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            grid[Point2::new(x, y)] = (seed >> 33) as u32 % 9 + 1;
+        }
+    }
+    grid
+}
+
+fn time(name: &str, mut search: impl FnMut() -> u32) {
+    let mut samples = Vec::with_capacity(RUNS as usize);
+    let mut cost = 0;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        cost = black_box(search());
+        samples.push(start.elapsed());
+    }
+    let stats = Stats::new(&mut samples);
+    println!(
+        "{name:<10} {:>10} {:>10} {:>10}  cost {cost}",
+        format_duration(stats.min),
+        format_duration(stats.median),
+        format_duration(stats.mean),
+    );
+}
+
+fn main() {
+    let grid = weights();
+    let start = Point2::ORIGIN;
+    let goal = Point2::new(SIZE as i64 - 1, SIZE as i64 - 1);
+    let weighted = |p: &Point2| {
+        grid.neighbours4(*p)
+            .map(|n| (n, grid[n]))
+            .collect::<Vec<_>>()
+    };
+
+    println!(
+        "{:<10} {:>10} {:>10} {:>10}",
+        "search", "min", "median", "mean"
+    );
+    time("bfs", || {
+        let steps = |p: &Point2| grid.neighbours4(*p).collect::<Vec<_>>();
+        bfs(start, steps, |p| *p == goal).unwrap().cost as u32
+    });
+    time("dijkstra", || {
+        dijkstra(start, weighted, |p| *p == goal).unwrap().cost
+    });
+    time("astar", || {
+        astar(
+            start,
+            weighted,
+            |p| p.manhattan(goal) as u32,
+            |p| *p == goal,
+        )
+        .unwrap()
+        .cost
+    });
+}
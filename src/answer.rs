@@ -0,0 +1,235 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use num_bigint::BigInt;
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// A puzzle answer. Constructors normalise, so equal answers compare equal whatever their
+/// source type: integers that fit in `i128` are always `Int`, and `Text` never holds an
+/// integer or more than one line.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Int(i128),
+    BigInt(BigInt),
+    Text(String),
+    /// Letters drawn as text art, one string per row.
+    Ocr(Vec<String>),
+}
+
+impl Answer {
+    pub fn as_int(&self) -> Option<i128> {
+        match self {
+            Answer::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// `Int` and `BigInt` answers as a big integer, so they can be ordered.
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Answer::Int(value) => Some(BigInt::from(*value)),
+            Answer::BigInt(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{value}"),
+            Answer::BigInt(value) => write!(f, "{value}"),
+            Answer::Text(text) => write!(f, "{text}"),
+            Answer::Ocr(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($int:ty),*) => {
+        $(impl From<$int> for Answer {
+            fn from(value: $int) -> Self {
+                Answer::Int(value.into())
+            }
+        })*
+    };
+}
+
+from_int!(u8, u16, u32, u64, i8, i16, i32, i64, i128);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::from(value as u64)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(value: isize) -> Self {
+        Answer::from(value as i64)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        match i128::try_from(value) {
+            Ok(value) => Answer::Int(value),
+            Err(_) => Answer::BigInt(value.into()),
+        }
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        match i128::try_from(&value) {
+            Ok(value) => Answer::Int(value),
+            Err(_) => Answer::BigInt(value),
+        }
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(rows: Vec<String>) -> Self {
+        Answer::Ocr(rows)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        text.as_str().into()
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        let text = text.trim_end_matches('\n');
+        if text.contains('\n') {
+            Answer::Ocr(text.lines().map(str::to_owned).collect())
+        } else if let Ok(value) = text.parse::<i128>() {
+            Answer::Int(value)
+        } else if let Ok(value) = text.parse::<BigInt>() {
+            Answer::BigInt(value)
+        } else {
+            Answer::Text(text.to_owned())
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Ok(text.into())
+    }
+}
+
+/// Integers are numbers when they fit in 64 bits and strings otherwise, text is a string and
+/// OCR answers are arrays of rows.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Int(value) => match (i64::try_from(*value), u64::try_from(*value)) {
+                (Ok(value), _) => serializer.serialize_i64(value),
+                (_, Ok(value)) => serializer.serialize_u64(value),
+                _ => serializer.collect_str(value),
+            },
+            Answer::BigInt(value) => serializer.collect_str(value),
+            Answer::Text(text) => serializer.serialize_str(text),
+            Answer::Ocr(rows) => rows.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AnswerVisitor)
+    }
+}
+
+struct AnswerVisitor;
+
+impl<'de> Visitor<'de> for AnswerVisitor {
+    type Value = Answer;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an integer, a string or an array of rows")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Answer, E> {
+        Ok(value.into())
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Answer, E> {
+        Ok(value.into())
+    }
+
+    fn visit_i128<E: de::Error>(self, value: i128) -> Result<Answer, E> {
+        Ok(value.into())
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<Answer, E> {
+        Ok(value.into())
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<Answer, E> {
+        Ok(text.into())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Answer, A::Error> {
+        let mut rows = Vec::new();
+        while let Some(row) = seq.next_element::<String>()? {
+            rows.push(row);
+        }
+        Ok(Answer::Ocr(rows))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigInt;
+
+    use super::Answer;
+
+    #[test]
+    fn test_integers_normalise() {
+        assert_eq!(Answer::from(42_u8), Answer::from(42_i64));
+        assert_eq!(Answer::from(42_u128), Answer::Int(42));
+        assert_eq!(Answer::from(BigInt::from(42)), Answer::Int(42));
+        assert_eq!(Answer::from("42"), Answer::Int(42));
+        assert_eq!(Answer::from(-7_i32).to_string(), "-7");
+        assert!(matches!(Answer::from(u128::MAX), Answer::BigInt(_)));
+        assert_eq!(Answer::from(u128::MAX.to_string()), Answer::from(u128::MAX));
+    }
+
+    #[test]
+    fn test_text_and_ocr() {
+        assert_eq!(
+            Answer::from("EHPZPJGL"),
+            Answer::Text("EHPZPJGL".to_owned())
+        );
+        let ocr = Answer::from("#..#\n####\n#..#\n");
+        assert_eq!(
+            ocr,
+            Answer::Ocr(vec!["#..#".into(), "####".into(), "#..#".into()])
+        );
+        assert_eq!(ocr.to_string(), "#..#\n####\n#..#");
+    }
+
+    #[test]
+    fn test_serde() {
+        let answers = vec![
+            Answer::from(71503_u64),
+            Answer::from(-3_i32),
+            Answer::from(u128::MAX),
+            Answer::from("abc"),
+            Answer::from(vec!["#.".to_owned(), ".#".to_owned()]),
+        ];
+        let json = serde_json::to_string(&answers).unwrap();
+        assert_eq!(
+            json,
+            format!(r##"[71503,-3,"{}","abc",["#.",".#"]]"##, u128::MAX)
+        );
+        assert_eq!(serde_json::from_str::<Vec<Answer>>(&json).unwrap(), answers);
+    }
+}
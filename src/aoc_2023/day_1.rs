@@ -1,29 +1,64 @@
 use rayon::{iter::ParallelIterator, str::ParallelString};
 
-#[allow(dead_code)]
-fn day_1_part_1(data: &str) -> u32 {
-    data.par_lines()
-        .map(|line| {
-            let chars = line.par_matches(char::is_numeric).collect::<Vec<&str>>();
-            (chars[0].to_owned() + chars[chars.len() - 1])
-                .parse::<u32>()
-                .unwrap()
-        })
-        .sum()
+use crate::{input::numbered_lines, AocError, ParseError, PuzzleId, Solution};
+
+const ID: PuzzleId = PuzzleId::new(2023, 1);
+
+pub struct Day1 {
+    data: String,
 }
 
-#[allow(dead_code)]
-fn day_1_part_2(data: &str) -> u32 {
-    data.par_lines()
-        .map(|line| {
-            let first_digit = find_digit(line, false);
-            let second_digit = find_digit(line, true);
-            (first_digit + &second_digit).parse::<u32>().unwrap()
+impl Solution for Day1 {
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            data: data.to_owned(),
         })
-        .sum()
+    }
+
+    fn part_1(&self) -> Result<u32, AocError> {
+        Ok(numbered_lines(&self.data)
+            .map(|(idx, line)| {
+                let chars = line.par_matches(char::is_numeric).collect::<Vec<&str>>();
+                if chars.is_empty() {
+                    return Err(ID.parse_error(idx, line, line, "no digit on this line"));
+                }
+                calibration_value(idx, line, chars[0].to_owned() + chars[chars.len() - 1])
+            })
+            .sum::<Result<u32, _>>()?)
+    }
+
+    fn part_2(&self) -> Result<u32, AocError> {
+        Ok(numbered_lines(&self.data)
+            .map(
+                |(idx, line)| match (find_digit(line, false), find_digit(line, true)) {
+                    (Some(first_digit), Some(second_digit)) => {
+                        calibration_value(idx, line, first_digit + &second_digit)
+                    }
+                    _ => Err(ID.parse_error(idx, line, line, "no digit on this line")),
+                },
+            )
+            .sum::<Result<u32, _>>()?)
+    }
+}
+
+pub fn day_1_part_1(data: &str) -> Result<u32, AocError> {
+    Day1::parse(data)?.part_1()
+}
+
+pub fn day_1_part_2(data: &str) -> Result<u32, AocError> {
+    Day1::parse(data)?.part_2()
+}
+
+fn calibration_value(idx: usize, line: &str, digits: String) -> Result<u32, ParseError> {
+    digits
+        .parse::<u32>()
+        .map_err(|_| ID.parse_error(idx, line, line, format!("`{digits}` is not a number")))
 }
 
-fn find_digit(data: &str, reverse: bool) -> String {
+fn find_digit(data: &str, reverse: bool) -> Option<String> {
     let chars: Vec<char> = if reverse {
         data.chars().rev().collect()
     } else {
@@ -33,14 +68,14 @@ fn find_digit(data: &str, reverse: bool) -> String {
     let mut spelled = "".to_owned();
     for char in chars {
         if char.is_numeric() {
-            return char.to_string();
+            return Some(char.to_string());
         }
         spelled += &char.to_string();
         if let Some(digit) = spelled_number(&spelled, reverse) {
-            return digit.to_owned();
+            return Some(digit.to_owned());
         }
     }
-    spelled_number(&spelled, reverse).unwrap().to_owned()
+    spelled_number(&spelled, reverse).map(str::to_owned)
 }
 
 fn spelled_number(spelled: &str, reverse: bool) -> Option<&'static str> {
@@ -77,19 +112,50 @@ fn match_digit(spelled: &str) -> Option<&'static str> {
 
 #[cfg(test)]
 mod test {
+    use crate::input::load_or_skip;
+
     use super::{day_1_part_1, day_1_part_2};
 
+    const EXAMPLE_1: &str = "1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+";
+
+    const EXAMPLE_2: &str = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
     #[test]
     fn test_day_1_part_1() {
-        let data = include_str!("../../data/aoc_2023/day_1.txt");
-        let solution = day_1_part_1(data);
+        let Some(data) = load_or_skip(2023, 1) else {
+            return;
+        };
+        let solution = day_1_part_1(&data).unwrap();
         println!("2023.1.1: {solution}");
     }
 
     #[test]
     fn test_day_1_part_2() {
-        let data = include_str!("../../data/aoc_2023/day_1.txt");
-        let solution = day_1_part_2(data);
+        let Some(data) = load_or_skip(2023, 1) else {
+            return;
+        };
+        let solution = day_1_part_2(&data).unwrap();
         println!("2023.1.2: {solution}");
     }
+
+    #[test]
+    fn test_day_1_part_1_example() {
+        assert_eq!(day_1_part_1(EXAMPLE_1).unwrap(), 142);
+    }
+
+    #[test]
+    fn test_day_1_part_2_example() {
+        assert_eq!(day_1_part_2(EXAMPLE_2).unwrap(), 281);
+    }
 }
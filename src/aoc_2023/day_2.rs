@@ -1,16 +1,27 @@
-use rayon::{
-    iter::{IntoParallelRefIterator, ParallelIterator},
-    str::ParallelString,
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::{
+    grammar::{complete, int, keyword, pair, preceded, separated, spaced, tag, Parser},
+    input::numbered_lines,
+    parse::TokenError,
+    AocError, ParseError, Part, PuzzleId, Solution,
+};
+
+const ID: PuzzleId = PuzzleId::new(2023, 2);
+
+const BAG: Subset = Subset {
+    red: 12,
+    green: 13,
+    blue: 14,
 };
 
-#[derive(Default)]
 struct Game {
     id: u32,
     subsets: Vec<Subset>,
 }
 
 impl Game {
-    fn filter(self, condition: Subset) -> Option<Self> {
+    fn filter(&self, condition: Subset) -> Option<&Self> {
         match self.subsets.par_iter().find_any(|set| {
             set.red > condition.red || set.green > condition.green || set.blue > condition.blue
         }) {
@@ -21,10 +32,10 @@ impl Game {
 }
 
 #[derive(Clone, Copy, Default)]
-struct Subset {
-    red: u8,
-    green: u8,
-    blue: u8,
+pub struct Subset {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
 }
 
 impl Subset {
@@ -33,90 +44,189 @@ impl Subset {
     }
 }
 
-#[allow(dead_code)]
-fn day_2_part_1(data: &str, condition: Subset) -> u32 {
-    data.par_lines()
-        .filter_map(|line| ugly_game_parser(line).filter(condition))
-        .fold_with(0, |acc, elem| acc + elem.id)
-        .sum()
+pub struct Day2 {
+    games: Vec<Game>,
 }
 
-#[allow(dead_code)]
-fn day_2_part_2(data: &str) -> u32 {
-    data.par_lines()
-        .map(|line| {
-            let mut max_set = Subset::default();
-            for set in ugly_game_parser(line).subsets {
-                if set.red > max_set.red {
-                    max_set.red = set.red
-                }
-                if set.green > max_set.green {
-                    max_set.green = set.green
-                }
-                if set.blue > max_set.blue {
-                    max_set.blue = set.blue
-                }
-            }
-            max_set.power()
-        })
-        .sum()
+impl Day2 {
+    fn possible_games(&self, condition: Subset) -> Result<u32, AocError> {
+        self.games
+            .par_iter()
+            .filter_map(|game| game.filter(condition))
+            .map(|game| Some(game.id))
+            .try_reduce(|| 0, u32::checked_add)
+            .ok_or_else(|| ID.unsolvable(Part::One, "the sum of game ids overflows u32"))
+    }
 }
 
-fn ugly_game_parser(data: &str) -> Game {
-    let mut game = Game::default();
-    let (id_str, subsets_str) = data.split_once(':').unwrap();
-    game.id = id_str[5..id_str.len()].parse::<u32>().unwrap();
-    for set in subsets_str.split(';') {
-        game.subsets.push(ugly_subset_parser(set));
+impl Solution for Day2 {
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            games: numbered_lines(data)
+                .map(|(idx, line)| parse_game(line).map_err(|err| ID.invalid(idx, line, err)))
+                .collect::<Result<_, _>>()?,
+        })
     }
-    game
-}
 
-fn ugly_subset_parser(data: &str) -> Subset {
-    let mut subset = Subset::default();
-
-    for cube in data.split(',') {
-        let count = cube
-            .par_matches(char::is_numeric)
-            .collect::<String>()
-            .parse::<u8>()
-            .unwrap();
-        if cube.contains("red") {
-            subset.red += count;
-        } else if cube.contains("green") {
-            subset.green += count;
-        } else if cube.contains("blue") {
-            subset.blue += count;
-        }
+    fn part_1(&self) -> Result<u32, AocError> {
+        self.possible_games(BAG)
     }
 
-    subset
+    fn part_2(&self) -> Result<u32, AocError> {
+        self.games
+            .par_iter()
+            .map(|game| {
+                let mut max_set = Subset::default();
+                for set in &game.subsets {
+                    if set.red > max_set.red {
+                        max_set.red = set.red
+                    }
+                    if set.green > max_set.green {
+                        max_set.green = set.green
+                    }
+                    if set.blue > max_set.blue {
+                        max_set.blue = set.blue
+                    }
+                }
+                Some(max_set.power())
+            })
+            .try_reduce(|| 0, u32::checked_add)
+            .ok_or_else(|| ID.unsolvable(Part::Two, "the sum of powers overflows u32"))
+    }
+}
+
+pub fn day_2_part_1(data: &str, condition: Subset) -> Result<u32, AocError> {
+    Day2::parse(data)?.possible_games(condition)
+}
+
+pub fn day_2_part_2(data: &str) -> Result<u32, AocError> {
+    Day2::parse(data)?.part_2()
+}
+
+#[derive(Clone, Copy)]
+enum Colour {
+    Red,
+    Green,
+    Blue,
+}
+
+fn parse_game<'a>(line: &'a str) -> Result<Game, TokenError<'a>> {
+    let colour = keyword(
+        &[
+            ("red", Colour::Red),
+            ("green", Colour::Green),
+            ("blue", Colour::Blue),
+        ],
+        "a cube colour",
+    );
+    // Keeps the count's text, to point at if the running total overflows.
+    let count = |input: &'a str| {
+        let input = input.trim_start();
+        let (rest, count) = int::<u8>().parse(input)?;
+        Ok((rest, (&input[..input.len() - rest.len()], count)))
+    };
+    let cube = pair(count, spaced(colour)).context("`<count> <colour>`");
+    let cubes = separated(cube, tag(","));
+    let subset = move |input: &'a str| {
+        let (rest, cubes) = cubes.parse(input)?;
+        let mut subset = Subset::default();
+        for ((token, count), colour) in cubes {
+            let total = match colour {
+                Colour::Red => &mut subset.red,
+                Colour::Green => &mut subset.green,
+                Colour::Blue => &mut subset.blue,
+            };
+            *total = total
+                .checked_add(count)
+                .ok_or(TokenError::OutOfRange(token))?;
+        }
+        Ok((rest, subset))
+    };
+    let game = pair(
+        preceded(tag("Game"), spaced(int())),
+        preceded(tag(":"), separated(subset, tag(";"))),
+    );
+    complete(game.map(|(id, subsets)| Game { id, subsets }), line)
 }
 
 #[cfg(test)]
 mod test {
+    use crate::{input::load_or_skip, AocError, Solution};
 
-    use crate::aoc_2023::day_2::Subset;
+    use super::{day_2_part_1, day_2_part_2, Day2, Subset, BAG};
 
-    use super::{day_2_part_1, day_2_part_2};
+    const EXAMPLE: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
 
     #[test]
     fn test_day_2_part_1() {
-        let data = include_str!("../../data/aoc_2023/day_2.txt");
+        let Some(data) = load_or_skip(2023, 2) else {
+            return;
+        };
         let condition = Subset {
             red: 12,
             green: 13,
             blue: 14,
         };
 
-        let solution = day_2_part_1(data, condition);
+        let solution = day_2_part_1(&data, condition).unwrap();
         println!("2023.2.1: {solution}");
     }
 
     #[test]
     fn test_day_2_part_2() {
-        let data = include_str!("../../data/aoc_2023/day_2.txt");
-        let solution = day_2_part_2(data);
+        let Some(data) = load_or_skip(2023, 2) else {
+            return;
+        };
+        let solution = day_2_part_2(&data).unwrap();
         println!("2023.2.2: {solution}");
     }
+
+    #[test]
+    fn test_day_2_parse_error() {
+        let data = "Game 1: 3 blue, 4 red\nGame 2: 1 blue, 2 purple\n";
+        let err = Day2::parse(data).err().unwrap();
+        assert_eq!((err.line, err.column), (2, 19));
+        assert_eq!(err.text, "purple");
+        assert!(matches!(
+            day_2_part_2("Game 1 3 blue"),
+            Err(AocError::Parse(_))
+        ));
+        let err = Day2::parse("Game 1: 200 red, 100 red").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 18));
+        assert_eq!(err.text, "100");
+    }
+
+    #[test]
+    fn test_day_2_overflow() {
+        let data = "Game 4294967295: 1 red\nGame 1: 1 red\n";
+        assert!(matches!(
+            day_2_part_1(data, BAG),
+            Err(AocError::Unsolvable { .. })
+        ));
+        let data = (1..=300)
+            .map(|id| format!("Game {id}: 255 red, 255 green, 255 blue\n"))
+            .collect::<String>();
+        assert!(matches!(
+            day_2_part_2(&data),
+            Err(AocError::Unsolvable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_day_2_part_1_example() {
+        assert_eq!(day_2_part_1(EXAMPLE, BAG).unwrap(), 8);
+    }
+
+    #[test]
+    fn test_day_2_part_2_example() {
+        assert_eq!(day_2_part_2(EXAMPLE).unwrap(), 2286);
+    }
 }
@@ -1,176 +1,181 @@
-use std::ops::Range;
+use std::collections::HashMap;
 
-use rayon::{
-    iter::{IntoParallelRefIterator, ParallelIterator},
-    str::ParallelString,
-};
+use crate::{geometry::Point2, grid::Grid, AocError, ParseError, Part, PuzzleId, Solution};
 
-struct Part {
-    number: u32,
-    x: Range<i32>,
-    y: Range<i32>,
-}
+const ID: PuzzleId = PuzzleId::new(2023, 3);
 
-impl Part {
-    fn filter(&self, symbols: &[Symbol]) -> Option<&Self> {
-        if symbols
-            .par_iter()
-            .any(|symbol| self.x.contains(&symbol.x) && self.y.contains(&symbol.y))
-        {
-            Some(self)
-        } else {
-            None
-        }
-    }
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Digit,
+    Symbol(char),
 }
 
-struct Symbol {
-    x: i32,
-    y: i32,
+struct PartNumber {
+    number: u32,
+    /// The symbols touching any of the number's digits, diagonals included.
+    symbols: Vec<Point2>,
 }
 
-impl Symbol {
-    fn gear_ratio(&self, parts: &[Part]) -> Option<u32> {
-        let parts = parts
-            .par_iter()
-            .filter(|part| part.x.contains(&self.x) && part.y.contains(&self.y))
-            .collect::<Vec<&Part>>();
-        if parts.len() == 2 {
-            Some(parts[0].number * parts[1].number)
-        } else {
-            None
-        }
-    }
+pub struct Day3 {
+    schematic: Grid<Cell>,
+    parts: Vec<PartNumber>,
 }
 
-#[allow(dead_code)]
-fn day_3_part_1(data: &str) -> u32 {
-    let parts = ugly_parts_parser(data);
-    let symbols = ugly_symbols_parser(data);
-
-    parts
-        .par_iter()
-        .filter_map(|part| part.filter(&symbols))
-        .fold_with(0, |acc, elem| acc + elem.number)
-        .sum()
-}
+impl Solution for Day3 {
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let schematic = Grid::parse(ID, data, |char| match char {
+            '.' => Ok(Cell::Empty),
+            '0'..='9' => Ok(Cell::Digit),
+            _ if char.is_ascii_punctuation() => Ok(Cell::Symbol(char)),
+            _ => Err("expected a digit, `.` or a symbol"),
+        })?;
+        let parts = data
+            .lines()
+            .take(schematic.height())
+            .enumerate()
+            .map(|(idx, line)| parse_parts(&schematic, idx, line))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            schematic,
+            parts: parts.into_iter().flatten().collect(),
+        })
+    }
 
-#[allow(dead_code)]
-fn day_3_part_2(data: &str) -> u32 {
-    let parts = ugly_parts_parser(data);
-    let maybe_gears = ugly_gears_parser(data);
-    maybe_gears
-        .par_iter()
-        .filter_map(|symbol| symbol.gear_ratio(&parts))
-        .sum()
-}
+    fn part_1(&self) -> Result<u32, AocError> {
+        self.parts
+            .iter()
+            .filter(|part| !part.symbols.is_empty())
+            .try_fold(0u32, |acc, part| acc.checked_add(part.number))
+            .ok_or_else(|| ID.unsolvable(Part::One, "the sum overflows u32"))
+    }
 
-fn ugly_parts_parser(data: &str) -> Vec<Part> {
-    data.lines()
-        .enumerate()
-        .flat_map(|(idx, line)| {
-            let mut numbers = Vec::new();
-            let mut accumulator = "".to_owned();
-            let mut start = None;
-
-            for (char_idx, char) in line.chars().enumerate() {
-                if char.is_numeric() {
-                    accumulator += &char.to_string();
-                    if start.is_none() {
-                        start = Some(char_idx);
-                    }
-                } else if let Some(val) = parse_part(&accumulator, idx, char_idx, &start) {
-                    numbers.push(val);
-                    start = None;
-                    accumulator = "".to_string();
-                }
-            }
-            if start.is_some() {
-                if let Some(val) = parse_part(&accumulator, idx, line.len(), &start) {
-                    numbers.push(val);
+    fn part_2(&self) -> Result<u32, AocError> {
+        let mut gears = HashMap::<Point2, Vec<u32>>::new();
+        for part in &self.parts {
+            for &symbol in &part.symbols {
+                if self.schematic[symbol] == Cell::Symbol('*') {
+                    gears.entry(symbol).or_default().push(part.number);
                 }
             }
-            numbers
-        })
-        .collect()
+        }
+        gears
+            .values()
+            .filter_map(|parts| match parts[..] {
+                [first, second] => Some(first.checked_mul(second)),
+                _ => None,
+            })
+            .try_fold(0u32, |acc, ratio| acc.checked_add(ratio?))
+            .ok_or_else(|| ID.unsolvable(Part::Two, "the gear ratios overflow u32"))
+    }
 }
 
-fn parse_part(
-    accumulator: &str,
-    idx: usize,
-    char_idx: usize,
-    start: &Option<usize>,
-) -> Option<Part> {
-    if let Ok(val) = accumulator.parse::<u32>() {
-        Some(Part {
-            number: val,
-            x: idx as i32 - 1..idx as i32 + 2,
-            y: start.unwrap() as i32 - 1..char_idx as i32 + 1, //we are already +1 the end of number
-        })
-    } else {
-        None
-    }
+pub fn day_3_part_1(data: &str) -> Result<u32, AocError> {
+    Day3::parse(data)?.part_1()
 }
 
-fn ugly_symbols_parser(data: &str) -> Vec<Symbol> {
-    data.lines()
-        .enumerate()
-        .flat_map(|(idx, line)| {
-            //let mut symbols = Vec::new();
-            line.par_char_indices()
-                .filter_map(|(sym_idx, sym)| {
-                    if sym.is_ascii_punctuation() && sym != '.' {
-                        Some(Symbol {
-                            x: idx as i32,
-                            y: sym_idx as i32,
-                        })
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<Symbol>>()
-        })
-        .collect()
+pub fn day_3_part_2(data: &str) -> Result<u32, AocError> {
+    Day3::parse(data)?.part_2()
 }
 
-fn ugly_gears_parser(data: &str) -> Vec<Symbol> {
-    data.lines()
-        .enumerate()
-        .flat_map(|(idx, line)| {
-            //let mut symbols = Vec::new();
-            line.par_char_indices()
-                .filter_map(|(sym_idx, sym)| {
-                    if sym == '*' {
-                        Some(Symbol {
-                            x: idx as i32,
-                            y: sym_idx as i32,
-                        })
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<Symbol>>()
-        })
-        .collect()
+/// The numbers on row `idx`, which the grid has already checked is ASCII.
+fn parse_parts(
+    schematic: &Grid<Cell>,
+    idx: usize,
+    line: &str,
+) -> Result<Vec<PartNumber>, ParseError> {
+    let row = schematic.row(idx);
+    let mut parts = Vec::new();
+    let mut start = 0;
+    while let Some(offset) = row[start..].iter().position(|&cell| cell == Cell::Digit) {
+        let first = start + offset;
+        let end = row[first..]
+            .iter()
+            .position(|&cell| cell != Cell::Digit)
+            .map_or(row.len(), |len| first + len);
+        let token = &line[first..end];
+        let number = token
+            .parse()
+            .map_err(|_| ID.parse_error(idx, line, token, "part number out of range"))?;
+        let mut symbols = (first..end)
+            .flat_map(|x| schematic.neighbours8(Point2::new(x as i64, idx as i64)))
+            .filter(|&point| matches!(schematic[point], Cell::Symbol(_)))
+            .collect::<Vec<_>>();
+        symbols.sort_unstable();
+        symbols.dedup();
+        parts.push(PartNumber { number, symbols });
+        start = end;
+    }
+    Ok(parts)
 }
 
 #[cfg(test)]
 mod test {
+    use crate::{input::load_or_skip, AocError};
 
     use super::{day_3_part_1, day_3_part_2};
 
+    const EXAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+";
+
     #[test]
     fn test_day_3_part_1() {
-        let data = include_str!("../../data/aoc_2023/day_3.txt");
+        let Some(data) = load_or_skip(2023, 3) else {
+            return;
+        };
 
-        let solution = day_3_part_1(data);
+        let solution = day_3_part_1(&data).unwrap();
         println!("2023.3.1: {solution}");
     }
 
     #[test]
     fn test_day_3_part_2() {
-        let data = include_str!("../../data/aoc_2023/day_3.txt");
-        let solution = day_3_part_2(data);
+        let Some(data) = load_or_skip(2023, 3) else {
+            return;
+        };
+        let solution = day_3_part_2(&data).unwrap();
         println!("2023.3.2: {solution}");
     }
+
+    #[test]
+    fn test_day_3_part_1_example() {
+        assert_eq!(day_3_part_1(EXAMPLE).unwrap(), 4361);
+    }
+
+    #[test]
+    fn test_day_3_part_2_example() {
+        assert_eq!(day_3_part_2(EXAMPLE).unwrap(), 467835);
+    }
+
+    #[test]
+    fn test_day_3_trailing_blank_line() {
+        let data = format!("{EXAMPLE}\n\n");
+        assert_eq!(day_3_part_1(&data).unwrap(), 4361);
+        assert_eq!(day_3_part_2(&data).unwrap(), 467835);
+    }
+
+    #[test]
+    fn test_day_3_overflow() {
+        assert!(matches!(
+            day_3_part_2("99999*99999\n"),
+            Err(AocError::Unsolvable { .. })
+        ));
+        let data = "999999999*999999999*999999999*999999999*999999999\n";
+        assert!(matches!(
+            day_3_part_1(data),
+            Err(AocError::Unsolvable { .. })
+        ));
+    }
 }
@@ -3,31 +3,29 @@ use std::{
     ops::Range,
 };
 
-use rayon::{
-    iter::{
-        IntoParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
-    },
-    str::ParallelString,
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::{
+    grammar::{complete, int, many, pair, preceded, spaced, tag, Parser},
+    input::numbered_lines,
+    parse::TokenError,
+    AocError, ParseError, Part, PuzzleId, Solution,
 };
-use regex::Regex;
+
+const ID: PuzzleId = PuzzleId::new(2023, 4);
 
 struct Card {
     id: u32,
-    winning_numbers: HashSet<u32>,
-    numbers: HashSet<u32>,
     winning_count: u32,
 }
 
 impl Card {
-    fn calculate_points(&mut self) -> i32 {
-        let not_winning = self.numbers.difference(&self.winning_numbers).count() as u32;
-        let winning_count = self.numbers.len() as u32 - not_winning;
-        self.winning_count = winning_count;
-
-        if not_winning == self.numbers.len() as u32 {
-            0
+    /// `None` if the points overflow.
+    fn calculate_points(&self) -> Option<u64> {
+        if self.winning_count == 0 {
+            Some(0)
         } else {
-            2_i32.pow(winning_count - 1)
+            2_u64.checked_pow(self.winning_count - 1)
         }
     }
 
@@ -36,63 +34,91 @@ impl Card {
     }
 }
 
-fn parse_cards(data: &str) -> Vec<Card> {
-    data.par_lines().map(parse_card).collect()
+fn parse_cards(data: &str) -> Result<Vec<Card>, ParseError> {
+    let cards = numbered_lines(data)
+        .map(|(idx, line)| parse_card(line).map_err(|err| ID.invalid(idx, line, err)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let ids = cards.iter().map(|card| card.id).collect::<HashSet<_>>();
+    match cards
+        .iter()
+        .zip(data.lines())
+        .enumerate()
+        .find(|(_, (card, _))| !card.next_cards().all(|id| ids.contains(&id)))
+    {
+        Some((idx, (card, line))) => Err(ID.parse_error(
+            idx,
+            line,
+            line,
+            format!(
+                "card {} wins copies of cards past the end of the table",
+                card.id
+            ),
+        )),
+        None => Ok(cards),
+    }
 }
 
-fn parse_card(line: &str) -> Card {
-    let re = Regex::new(r"\d+").unwrap();
-    let (id, all_numbers) = line.split_once(':').unwrap();
-    let (winning_numbers, numbers) = all_numbers.split_once('|').unwrap();
-
-    let id = re
-        .find_iter(id)
-        .map(|val| val.as_str().parse::<u32>().unwrap())
-        .last()
-        .unwrap();
-    let winning_numbers = re
-        .find_iter(winning_numbers)
-        .map(|val| val.as_str().parse::<u32>().unwrap())
-        .collect::<HashSet<u32>>();
-    let numbers = re
-        .find_iter(numbers)
-        .map(|val| val.as_str().parse::<u32>().unwrap())
-        .collect::<HashSet<u32>>();
-    Card {
+fn parse_card(line: &str) -> Result<Card, TokenError<'_>> {
+    let numbers = || many(spaced(int())).map(HashSet::<u32>::from_iter);
+    let card = pair(
+        preceded(tag("Card"), spaced(int())),
+        preceded(
+            spaced(tag(":")),
+            pair(numbers(), preceded(spaced(tag("|")), numbers())),
+        ),
+    );
+    let card = card.map(|(id, (winning, numbers))| Card {
         id,
-        winning_numbers,
-        numbers,
-        winning_count: 0,
-    }
+        winning_count: numbers.intersection(&winning).count() as u32,
+    });
+    complete(card, line)
 }
 
-#[allow(dead_code)]
-fn day_4_part_1(data: &str) -> i32 {
-    parse_cards(data)
-        .par_iter_mut()
-        .map(|card| card.calculate_points())
-        .sum()
+pub struct Day4 {
+    cards: Vec<Card>,
 }
 
-#[allow(dead_code)]
-fn day_4_part_2(data: &str) -> u32 {
-    let mut cards = parse_cards(data);
-    let winning_map = cards
-        .par_iter_mut()
-        .map(|card| {
-            card.calculate_points();
-            (card.id, card)
+impl Solution for Day4 {
+    type Answer1 = u64;
+    type Answer2 = u32;
+
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            cards: parse_cards(data)?,
         })
-        .into_par_iter()
-        .collect::<HashMap<_, _>>();
+    }
 
-    winning_map
-        .par_iter()
-        .map(|(_id, card)| calculate(card.next_cards(), &winning_map))
-        .sum()
+    fn part_1(&self) -> Result<u64, AocError> {
+        self.cards
+            .par_iter()
+            .map(|card| card.calculate_points())
+            .try_reduce(|| 0, u64::checked_add)
+            .ok_or_else(|| ID.unsolvable(Part::One, "the points overflow u64"))
+    }
+
+    fn part_2(&self) -> Result<u32, AocError> {
+        let winning_map = self
+            .cards
+            .par_iter()
+            .map(|card| (card.id, card))
+            .collect::<HashMap<_, _>>();
+
+        Ok(winning_map
+            .par_iter()
+            .map(|(_id, card)| calculate(card.next_cards(), &winning_map))
+            .sum())
+    }
+}
+
+pub fn day_4_part_1(data: &str) -> Result<u64, AocError> {
+    Day4::parse(data)?.part_1()
 }
 
-fn calculate(range: Range<u32>, map: &HashMap<u32, &mut Card>) -> u32 {
+pub fn day_4_part_2(data: &str) -> Result<u32, AocError> {
+    Day4::parse(data)?.part_2()
+}
+
+fn calculate(range: Range<u32>, map: &HashMap<u32, &Card>) -> u32 {
     let mut accumulator = 1;
     for id in range {
         accumulator += calculate(map[&id].next_cards(), map);
@@ -102,21 +128,75 @@ fn calculate(range: Range<u32>, map: &HashMap<u32, &mut Card>) -> u32 {
 
 #[cfg(test)]
 mod test {
+    use crate::{input::load_or_skip, AocError, Solution};
+
+    use super::{day_4_part_1, day_4_part_2, Day4};
 
-    use super::{day_4_part_1, day_4_part_2};
+    const EXAMPLE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
 
     #[test]
     fn test_day_4_part_1() {
-        let data = include_str!("../../data/aoc_2023/day_4.txt");
+        let Some(data) = load_or_skip(2023, 4) else {
+            return;
+        };
 
-        let solution = day_4_part_1(data);
+        let solution = day_4_part_1(&data).unwrap();
         println!("2023.4.1: {solution}");
     }
 
     #[test]
     fn test_day_4_part_2() {
-        let data = include_str!("../../data/aoc_2023/day_4.txt");
-        let solution = day_4_part_2(data);
+        let Some(data) = load_or_skip(2023, 4) else {
+            return;
+        };
+        let solution = day_4_part_2(&data).unwrap();
         println!("2023.4.2: {solution}");
     }
+
+    #[test]
+    fn test_day_4_parse_error() {
+        let err = Day4::parse("Card 1: 41 48 | 83 4x\n").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 21));
+        assert_eq!(err.reason, "expected end of line");
+        let err = Day4::parse("Card 1 41 | 83\n").err().unwrap();
+        assert_eq!((err.column, err.text.as_str()), (8, "41"));
+    }
+
+    /// A first card matching `matches` numbers, followed by the losing cards it wins copies of.
+    fn lucky_table(matches: u32) -> String {
+        let numbers = (1..=matches)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut table = format!("Card 1: {numbers} | {numbers}\n");
+        for id in 2..=matches + 1 {
+            table.push_str(&format!("Card {id}: 1 | 2\n"));
+        }
+        table
+    }
+
+    #[test]
+    fn test_day_4_part_1_overflow() {
+        assert_eq!(day_4_part_1(&lucky_table(33)).unwrap(), 1 << 32);
+        assert!(matches!(
+            day_4_part_1(&lucky_table(65)),
+            Err(AocError::Unsolvable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_day_4_part_1_example() {
+        assert_eq!(day_4_part_1(EXAMPLE).unwrap(), 13);
+    }
+
+    #[test]
+    fn test_day_4_part_2_example() {
+        assert_eq!(day_4_part_2(EXAMPLE).unwrap(), 30);
+    }
 }
@@ -1,167 +1,284 @@
-use std::{collections::VecDeque, ops::Range};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{
-    IntoParallelRefIterator, ParallelBridge, ParallelIterator,
+use crate::{
+    interval::{Interval, IntervalSet},
+    parse::{self, Section, TokenError},
+    range_map::{RangeMap, RangeMapError, Shift},
+    AocError, ParseError, Part, PuzzleId, Solution,
 };
-use regex::Regex;
 
-#[derive(Default, Debug)]
-struct Mapping {
-    source: u32,
-    source_range: Range<usize>,
-    destination: u32,
-}
+const ID: PuzzleId = PuzzleId::new(2023, 5);
+
 #[derive(Default, Debug)]
 struct GlobalMap {
-    seeds: Vec<u32>,
-    seed_to_soil: Vec<Mapping>,
-    soil_to_fertilizer: Vec<Mapping>,
-    fertilizer_to_water: Vec<Mapping>,
-    water_to_light: Vec<Mapping>,
-    light_to_temperature: Vec<Mapping>,
-    temperature_to_humidity: Vec<Mapping>,
-    humidity_to_location: Vec<Mapping>,
+    seeds: Vec<i64>,
+    seed_to_soil: RangeMap,
+    soil_to_fertilizer: RangeMap,
+    fertilizer_to_water: RangeMap,
+    water_to_light: RangeMap,
+    light_to_temperature: RangeMap,
+    temperature_to_humidity: RangeMap,
+    humidity_to_location: RangeMap,
 }
 
 impl GlobalMap {
-    fn lowest_location(&self) -> u32 {
-        self.seeds
-            .par_iter()
-            .map(|seed| self.location(*seed))
-            .min()
-            .unwrap()
+    fn lowest_location(&self) -> Option<i64> {
+        self.seeds.par_iter().map(|seed| self.location(*seed)).min()
     }
 
-    fn location(&self, seed: u32) -> u32 {
-        let soil = Self::find_in_range(seed, &self.seed_to_soil);
-        let fertilizer = Self::find_in_range(soil, &self.soil_to_fertilizer);
-        let water = Self::find_in_range(fertilizer, &self.fertilizer_to_water);
-        let light = Self::find_in_range(water, &self.water_to_light);
-        let temperature = Self::find_in_range(light, &self.light_to_temperature);
-        let humidity = Self::find_in_range(temperature, &self.temperature_to_humidity);
-        Self::find_in_range(humidity, &self.humidity_to_location)
+    fn stages(&self) -> [&RangeMap; 7] {
+        [
+            &self.seed_to_soil,
+            &self.soil_to_fertilizer,
+            &self.fertilizer_to_water,
+            &self.water_to_light,
+            &self.light_to_temperature,
+            &self.temperature_to_humidity,
+            &self.humidity_to_location,
+        ]
     }
 
-    fn lowest_location_expand(&self) -> u32 {
-        let seeds = self.expand_seeds();
-        seeds
+    fn location(&self, seed: i64) -> i64 {
+        self.stages()
             .into_iter()
-            .map(|range| {
-                let mut min = u32::MAX;
-                for seed in range {
-                    let loc = self.location(seed);
-                    if loc < min {
-                        min = loc;
-                    }
-                }
-                min
-            })
-            .min()
-            .unwrap()
+            .fold(seed, |value, map| map.get(value))
     }
 
-    fn expand_seeds(&self) -> Vec<Range<u32>> {
-        let mut cloned_seeds: VecDeque<u32> = self.seeds.clone().into();
-        cloned_seeds.push_front(0);
-        let mut end = cloned_seeds.into_iter().step_by(2);
-        end.next();
-
-        self.seeds
-            .iter()
-            .step_by(2)
-            .zip(end)
-            .par_bridge()
-            .map(|(x, y)| (*x..*x + y))
-            .collect()
+    /// The seed in the `(start, length)` ranges with the lowest location, and that location.
+    fn lowest_location_ranges(&self) -> Option<(i64, i64)> {
+        let seeds = self
+            .seeds
+            .chunks(2)
+            .map(|pair| Interval::with_len(pair[0], pair[1]))
+            .collect::<IntervalSet>();
+        let seed_to_location = self
+            .stages()
+            .into_iter()
+            .fold(RangeMap::default(), |map, stage| map.compose(stage));
+        // Each piece moves as one block, so its first seed is its lowest location.
+        seed_to_location
+            .pieces(&seeds)
+            .into_iter()
+            .map(|piece| (piece.source.start, piece.destination().start))
+            .min_by_key(|&(seed, location)| (location, seed))
     }
+}
 
-    fn find_in_range(source: u32, mapping: &Vec<Mapping>) -> u32 {
-        mapping
-            .par_iter()
-            .filter(|mapping| mapping.source_range.contains(&(source as usize)))
-            .min_by(|x, y| x.destination.cmp(&y.destination))
-            .map(|mapping| {
-                let step = source - mapping.source;
-                mapping.destination + step
-            })
-            .unwrap_or(source)
+fn parse_global_map(data: &str) -> Result<GlobalMap, ParseError> {
+    let mut map = GlobalMap::default();
+    let seeds_line = data.lines().next().unwrap_or_default();
+    let invalid = |err| ID.invalid(0, seeds_line, err);
+    let seeds = parse::labelled(seeds_line, "seeds").map_err(invalid)?;
+    map.seeds = parse::ints::<u32>(seeds)
+        .map(|seed| seed.map(i64::from))
+        .collect::<Result<_, _>>()
+        .map_err(invalid)?;
+    if map.seeds.is_empty() {
+        return Err(ID.parse_error(0, seeds_line, seeds, "expected at least one seed"));
     }
+
+    let sections = parse::sections(data).skip(1).collect::<Vec<_>>();
+    let mappings = |name: &'static str| {
+        sections
+            .iter()
+            .find(|section| parse::labelled(section.first_line(), name).is_ok())
+            .ok_or_else(|| ID.missing(data, format!("missing `{name}:` section")))
+            .and_then(parse_mappings)
+    };
+    map.seed_to_soil = mappings("seed-to-soil map")?;
+    map.soil_to_fertilizer = mappings("soil-to-fertilizer map")?;
+    map.fertilizer_to_water = mappings("fertilizer-to-water map")?;
+    map.water_to_light = mappings("water-to-light map")?;
+    map.light_to_temperature = mappings("light-to-temperature map")?;
+    map.temperature_to_humidity = mappings("temperature-to-humidity map")?;
+    map.humidity_to_location = mappings("humidity-to-location map")?;
+
+    Ok(map)
 }
 
-fn parse_global_map(data: &str) -> GlobalMap {
-    let mut map = GlobalMap::default();
-    let re = Regex::new(r"\d+").unwrap();
-    map.seeds = re
-        .find_iter(data.lines().next().unwrap())
-        .map(|val| val.as_str().parse::<u32>().unwrap())
-        .collect();
-
-    map.seed_to_soil = parse_range(&re, data, find_header(data, "seed-to-soil"));
-    map.soil_to_fertilizer = parse_range(&re, data, find_header(data, "soil-to-fertilizer"));
-    map.fertilizer_to_water = parse_range(&re, data, find_header(data, "fertilizer-to-water"));
-    map.water_to_light = parse_range(&re, data, find_header(data, "water-to-light"));
-    map.light_to_temperature = parse_range(&re, data, find_header(data, "light-to-temperature"));
-    map.temperature_to_humidity =
-        parse_range(&re, data, find_header(data, "temperature-to-humidity"));
-    map.humidity_to_location = parse_range(&re, data, find_header(data, "humidity-to-location"));
-
-    map
+fn parse_mappings(section: &Section) -> Result<RangeMap, ParseError> {
+    let shifts = section
+        .lines()
+        .skip(1)
+        .map(|(idx, line)| {
+            let [destination, source, length] =
+                parse::int_array::<u32, 3>(line).map_err(|err| match err {
+                    TokenError::Count { .. } => ID.parse_error(
+                        idx,
+                        line,
+                        line,
+                        "expected `<destination> <source> <length>`",
+                    ),
+                    err => ID.invalid(idx, line, err),
+                })?;
+            Ok(Shift::from_lengths(
+                destination.into(),
+                source.into(),
+                length.into(),
+            ))
+        })
+        .collect::<Result<Vec<_>, ParseError>>()?;
+    RangeMap::new(shifts.iter().copied()).map_err(|err| {
+        let culprit = match err {
+            RangeMapError::Overlap { second, .. } => Some(second),
+            RangeMapError::NotInvertible { .. } => None,
+        };
+        let (idx, line) = section
+            .lines()
+            .skip(1)
+            .zip(&shifts)
+            .filter(|(_, shift)| Some(shift.source) == culprit)
+            .map(|(line, _)| line)
+            .last()
+            .unwrap_or((section.start, section.first_line()));
+        ID.parse_error(idx, line, line, err.to_string())
+    })
 }
 
-fn find_header(data: &str, hint: &str) -> usize {
-    data.lines()
-        .enumerate()
-        .find_map(|(id, line)| line.contains(hint).then(|| id + 1))
-        .unwrap()
+pub struct Day5 {
+    map: GlobalMap,
 }
 
-fn parse_range(regex: &Regex, data: &str, start: usize) -> Vec<Mapping> {
-    data.lines()
-        .enumerate()
-        .filter(|(id, _)| id >= &start)
-        .map(|(_, line)| line)
-        .take_while(|line| !line.is_empty())
-        .map(|line| {
-            let numbers = regex
-                .find_iter(line)
-                .map(|val| val.as_str().parse::<u32>().unwrap())
-                .collect::<Vec<_>>();
-            Mapping {
-                source: numbers[1],
-                source_range: numbers[1] as usize..numbers[1] as usize + numbers[2] as usize,
-                destination: numbers[0],
-            }
+impl Solution for Day5 {
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            map: parse_global_map(data)?,
         })
-        .collect()
+    }
+
+    fn part_1(&self) -> Result<i64, AocError> {
+        self.map
+            .lowest_location()
+            .ok_or_else(|| ID.unsolvable(Part::One, "no seeds"))
+    }
+
+    fn part_2(&self) -> Result<i64, AocError> {
+        self.lowest_seed().map(|(_, location)| location)
+    }
+}
+
+impl Day5 {
+    /// The part 2 seed that ends up at the lowest location, and that location.
+    pub fn lowest_seed(&self) -> Result<(i64, i64), AocError> {
+        if !self.map.seeds.len().is_multiple_of(2) {
+            return Err(ID.unsolvable(Part::Two, "seeds do not come in (start, length) pairs"));
+        }
+        self.map
+            .lowest_location_ranges()
+            .ok_or_else(|| ID.unsolvable(Part::Two, "no seeds"))
+    }
 }
 
-#[allow(dead_code)]
-fn day_5_part_1(data: &str) -> u32 {
-    parse_global_map(data).lowest_location()
+pub fn day_5_part_1(data: &str) -> Result<i64, AocError> {
+    Day5::parse(data)?.part_1()
 }
 
-#[allow(dead_code)]
-fn day_5_part_2(data: &str) -> u32 {
-    parse_global_map(data).lowest_location_expand()
+pub fn day_5_part_2(data: &str) -> Result<i64, AocError> {
+    Day5::parse(data)?.part_2()
 }
 
 #[cfg(test)]
 mod test {
+    use crate::input::load_or_skip;
+
+    use crate::Solution;
+
+    use super::{day_5_part_1, day_5_part_2, Day5};
+
+    const EXAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
 
-    use super::{day_5_part_1, day_5_part_2};
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
 
     #[test]
     fn test_day_5_part_1() {
-        let data = include_str!("../../data/aoc_2023/day_5.txt");
+        let Some(data) = load_or_skip(2023, 5) else {
+            return;
+        };
 
-        let solution = day_5_part_1(data);
+        let solution = day_5_part_1(&data).unwrap();
         println!("2023.5.1: {solution}");
     }
 
     #[test]
     fn test_day_5_part_2() {
-        let data = include_str!("../../data/aoc_2023/day_5.txt");
-        let solution = day_5_part_2(data);
+        let Some(data) = load_or_skip(2023, 5) else {
+            return;
+        };
+        let solution = day_5_part_2(&data).unwrap();
         println!("2023.5.2: {solution}");
     }
+
+    #[test]
+    fn test_day_5_parse_error() {
+        let data = EXAMPLE.replace("39 0 15", "39 -1 15");
+        let err = Day5::parse(&data).err().unwrap();
+        assert_eq!((err.line, err.column), (10, 4));
+        assert_eq!(err.text, "-1");
+        let err = Day5::parse(&EXAMPLE.replace("0 69 1", "0 69"))
+            .err()
+            .unwrap();
+        assert_eq!(err.reason, "expected `<destination> <source> <length>`");
+        let err = Day5::parse(&EXAMPLE.replace("37 52 2", "37 50 2"))
+            .err()
+            .unwrap();
+        assert_eq!((err.line, err.column), (9, 1));
+        assert_eq!(err.reason, "range 50..52 overlaps range 15..52");
+        let err = Day5::parse(&EXAMPLE.replace("50 98 2", "50 50 10"))
+            .err()
+            .unwrap();
+        assert_eq!((err.line, err.column), (5, 1));
+        assert_eq!(err.reason, "range 50..98 overlaps range 50..60");
+    }
+
+    #[test]
+    fn test_day_5_lowest_seed() {
+        let day = Day5::parse(EXAMPLE).unwrap();
+        assert_eq!(day.lowest_seed().unwrap(), (82, 46));
+        let empty = Day5::parse(&EXAMPLE.replace("79 14 55 13", "79 0")).unwrap();
+        assert!(empty.lowest_seed().is_err());
+    }
+
+    #[test]
+    fn test_day_5_part_1_example() {
+        assert_eq!(day_5_part_1(EXAMPLE).unwrap(), 35);
+    }
+
+    #[test]
+    fn test_day_5_part_2_example() {
+        assert_eq!(day_5_part_2(EXAMPLE).unwrap(), 46);
+    }
 }
@@ -1,85 +1,410 @@
+use std::ops::{Add, Div, Mul, RangeInclusive, Sub};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use regex::Regex;
+use num_bigint::BigInt;
 
-#[derive(Debug)]
-struct Race {
-    time: u64,
-    distance: u64,
+use crate::{math, parse, Answer, AocError, ParseError, Part, PuzzleId, Solution};
+
+const ID: PuzzleId = PuzzleId::new(2023, 6);
+
+/// A boat race: holding the button for `h` ms charges the boat to `start_speed +
+/// acceleration * h` mm/ms, capped at `max_speed`, and it then moves for the rest of `time`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Race {
+    pub time: u64,
+    /// The record distance to beat.
+    pub distance: u64,
+    pub acceleration: u64,
+    pub start_speed: u64,
+    /// The longest the button can be held.
+    pub max_hold: Option<u64>,
+    /// Drag stops the boat going faster than this, however long it charges.
+    pub max_speed: Option<u64>,
 }
 
 impl Race {
+    /// The puzzle's race: 1 mm/ms faster per ms held, starting still, with no limits.
+    pub fn new(time: u64, distance: u64) -> Self {
+        Self {
+            time,
+            distance,
+            acceleration: 1,
+            start_speed: 0,
+            max_hold: None,
+            max_speed: None,
+        }
+    }
+
+    pub fn with_acceleration(self, acceleration: u64) -> Self {
+        Self {
+            acceleration,
+            ..self
+        }
+    }
+
+    pub fn with_start_speed(self, start_speed: u64) -> Self {
+        Self {
+            start_speed,
+            ..self
+        }
+    }
+
+    pub fn with_max_hold(self, max_hold: u64) -> Self {
+        Self {
+            max_hold: Some(max_hold),
+            ..self
+        }
+    }
+
+    pub fn with_max_speed(self, max_speed: u64) -> Self {
+        Self {
+            max_speed: Some(max_speed),
+            ..self
+        }
+    }
+
+    /// The longest allowed hold.
+    fn longest_hold(&self) -> u64 {
+        self.max_hold.map_or(self.time, |max| max.min(self.time))
+    }
+
+    /// How far the boat goes after holding for `hold` ms, if that hold is allowed. Saturates
+    /// rather than overflowing, which cannot change whether a `u64` record is beaten.
+    pub fn travelled(&self, hold: u64) -> Option<u128> {
+        if hold > self.longest_hold() {
+            return None;
+        }
+        let speed = u128::from(self.start_speed)
+            .saturating_add(u128::from(self.acceleration) * u128::from(hold));
+        let speed = self
+            .max_speed
+            .map_or(speed, |max| speed.min(u128::from(max)));
+        Some(speed.saturating_mul(u128::from(self.time - hold)))
+    }
+
+    fn beats(&self, hold: u64) -> bool {
+        self.travelled(hold)
+            .is_some_and(|travelled| travelled > u128::from(self.distance))
+    }
+
+    /// The shortest hold that goes furthest, with that distance.
+    pub fn optimal_hold(&self) -> (u64, u128) {
+        let longest = self.longest_hold();
+        // Past the hold that reaches top speed, holding longer only loses time.
+        let charged = match self.max_speed {
+            Some(max) if self.start_speed >= max => 0,
+            Some(max) if self.acceleration > 0 => (max - self.start_speed)
+                .div_ceil(self.acceleration)
+                .min(longest),
+            _ => longest,
+        };
+        // Before that the distance is a downward parabola peaking at
+        // `(acceleration * time - start_speed) / (2 * acceleration)`.
+        let peak = if self.acceleration == 0 {
+            0
+        } else {
+            let numerator = (u128::from(self.acceleration) * u128::from(self.time))
+                .saturating_sub(u128::from(self.start_speed));
+            let peak = numerator / (2 * u128::from(self.acceleration));
+            u64::try_from(peak).unwrap_or(u64::MAX).min(charged)
+        };
+        [0, peak, peak.saturating_add(1).min(charged), charged]
+            .into_iter()
+            .map(|hold| (hold, self.travelled(hold).unwrap_or_default()))
+            .fold((0, 0), |best, candidate| {
+                if candidate.1 > best.1 || (candidate.1 == best.1 && candidate.0 < best.0) {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// Every hold that beats the record. The distance only rises up to the optimal hold and
+    /// only falls after it, so the winners form a single range.
+    pub fn winning_holds(&self) -> Option<RangeInclusive<u64>> {
+        let (best, _) = self.optimal_hold();
+        if !self.beats(best) {
+            return None;
+        }
+        let first = first_true(0, best, |hold| self.beats(hold));
+        let last = last_true(best, self.longest_hold(), |hold| self.beats(hold));
+        Some(first..=last)
+    }
+
+    /// How many holds beat the record, in closed form for the puzzle's physics.
     fn winning_combo(&self) -> u64 {
-        let discriminant = (self.time * self.time - 4 * self.distance) as f64;
-        let sqrt_discriminant = discriminant.sqrt();
-        let min_hold_time = (((self.time as f64) - sqrt_discriminant) / 2.0).floor() as u64;
-        let max_hold_time = (((self.time as f64) + sqrt_discriminant) / 2.0).ceil() as u64;
-        max_hold_time - min_hold_time - 1
+        if *self == Race::new(self.time, self.distance) {
+            // Squaring a `u64` always fits in `u128`, and at most `time - 1` holds win.
+            let count =
+                winning_count_exact(u128::from(self.time), u128::from(self.distance), |n| {
+                    math::isqrt(n).expect("the discriminant is not negative")
+                });
+            return count as u64;
+        }
+        self.winning_holds()
+            .map_or(0, |holds| holds.end() - holds.start() + 1)
     }
 }
 
-#[allow(dead_code)]
-fn day_6_part_1(data: &str) -> u64 {
-    let races = parse_races(data);
-    races
-        .par_iter()
-        .map(|race| race.winning_combo())
-        .reduce(|| 1, |a, b| a * b)
+/// The first value in `low..=high` where monotone `pred` holds; it must hold at `high`.
+fn first_true(mut low: u64, mut high: u64, pred: impl Fn(u64) -> bool) -> u64 {
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if pred(mid) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    low
 }
 
-#[allow(dead_code)]
-fn day_6_part_2(data: &str) -> u64 {
-    let races = parse_races(data);
-    let race = races
-        .iter()
-        .map(|race| (race.time.to_string(), race.distance.to_string()))
-        .reduce(|(acc_time, acc_distance), (time, distance)| {
-            (acc_time + &time, acc_distance + &distance)
-        })
-        .map(|(t, d)| Race {
-            time: t.parse::<u64>().unwrap(),
-            distance: d.parse::<u64>().unwrap(),
-        })
-        .unwrap();
-    race.winning_combo()
+/// The last value in `low..=high` where monotone `pred` holds; it must hold at `low`.
+fn last_true(mut low: u64, mut high: u64, pred: impl Fn(u64) -> bool) -> u64 {
+    while low < high {
+        let mid = low + (high - low) / 2 + 1;
+        if pred(mid) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    low
+}
+
+/// The number of whole holds `h` in `0..=time` with `h * (time - h) > record`, which are the
+/// integers strictly between the roots of `h² - time·h + record`.
+fn winning_count_exact<T>(time: T, record: T, isqrt: impl Fn(T) -> T) -> T
+where
+    T: Clone
+        + Ord
+        + From<u8>
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Div<Output = T>,
+{
+    let (zero, one, two) = (T::from(0), T::from(1), T::from(2));
+    let square = time.clone() * time.clone();
+    let four_record = T::from(4) * record.clone();
+    if square < four_record {
+        return zero;
+    }
+    let root = isqrt(square - four_record);
+    let beats = |hold: &T| hold.clone() * (time.clone() - hold.clone()) > record;
+    // The floored root puts this within a step of the shortest winning hold.
+    let half = time.clone() / two.clone();
+    let mut shortest = (time.clone() - root) / two.clone();
+    while shortest <= half && !beats(&shortest) {
+        shortest = shortest + one.clone();
+    }
+    while shortest > zero && beats(&(shortest.clone() - one.clone())) {
+        shortest = shortest - one.clone();
+    }
+    if shortest > half {
+        zero
+    } else {
+        time - two * shortest + one
+    }
 }
 
-fn parse_races(data: &str) -> Vec<Race> {
-    let re = Regex::new(r"\d+").unwrap();
-    let numbers = re
-        .find_iter(data)
-        .map(|val| val.as_str().parse::<u64>().unwrap())
-        .collect::<Vec<u64>>();
-    let time = numbers.iter().take(numbers.len() / 2);
-    let distance = numbers.iter().skip(numbers.len() / 2);
-    time.zip(distance)
-        .map(|(time, distance)| Race {
-            time: *time,
-            distance: *distance,
+pub struct Day6 {
+    races: Vec<Race>,
+}
+
+impl Solution for Day6 {
+    type Answer1 = u64;
+    type Answer2 = Answer;
+
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            races: parse_races(data)?,
         })
-        .collect()
+    }
+
+    fn part_1(&self) -> Result<u64, AocError> {
+        self.races
+            .iter()
+            .try_fold(1u64, |acc, race| acc.checked_mul(race.winning_combo()))
+            .ok_or_else(|| ID.unsolvable(Part::One, "the product overflows u64"))
+    }
+
+    fn part_2(&self) -> Result<Answer, AocError> {
+        let (time, distance) = self
+            .races
+            .iter()
+            .map(|race| (race.time.to_string(), race.distance.to_string()))
+            .reduce(|(acc_time, acc_distance), (time, distance)| {
+                (acc_time + &time, acc_distance + &distance)
+            })
+            .ok_or_else(|| ID.unsolvable(Part::Two, "no races"))?;
+        if let (Ok(time), Ok(distance)) = (time.parse(), distance.parse()) {
+            return Ok(Race::new(time, distance).winning_combo().into());
+        }
+        // Too long for u64: the digits parsed as numbers before, so they parse again here.
+        let (time, distance) = (
+            time.parse::<BigInt>().expect("concatenated digits"),
+            distance.parse::<BigInt>().expect("concatenated digits"),
+        );
+        Ok(winning_count_exact(time, distance, |n| n.sqrt()).into())
+    }
+}
+
+pub fn day_6_part_1(data: &str) -> Result<u64, AocError> {
+    Day6::parse(data)?.part_1()
+}
+
+pub fn day_6_part_2(data: &str) -> Result<Answer, AocError> {
+    Day6::parse(data)?.part_2()
+}
+
+fn parse_races(data: &str) -> Result<Vec<Race>, ParseError> {
+    let mut lines = data.lines();
+    let time_line = lines.next().unwrap_or_default();
+    let distance_line = lines.next().unwrap_or_default();
+    let time = parse_line(0, time_line, "Time")?;
+    let distance = parse_line(1, distance_line, "Distance")?;
+    if time.is_empty() {
+        return Err(ID.parse_error(0, time_line, time_line, "expected at least one race"));
+    }
+    if time.len() != distance.len() {
+        return Err(ID.parse_error(
+            1,
+            distance_line,
+            distance_line,
+            format!("expected {} distances", time.len()),
+        ));
+    }
+    Ok(time
+        .into_iter()
+        .zip(distance)
+        .map(|(time, distance)| Race::new(time, distance))
+        .collect())
+}
+
+fn parse_line(idx: usize, line: &str, label: &'static str) -> Result<Vec<u64>, ParseError> {
+    let invalid = |err| ID.invalid(idx, line, err);
+    parse::ints(parse::labelled(line, label).map_err(invalid)?)
+        .collect::<Result<_, _>>()
+        .map_err(invalid)
 }
 
 #[cfg(test)]
 mod test {
+    use std::cmp::Reverse;
+
+    use crate::input::load_or_skip;
 
     use crate::aoc_2023::day_6::day_6_part_2;
 
-    use super::day_6_part_1;
+    use super::{day_6_part_1, Race};
+
+    const EXAMPLE: &str = "Time:      7  15   30
+Distance:  9  40  200
+";
 
     #[test]
     fn test_day_6_part_1() {
-        let data = include_str!("../../data/aoc_2023/day_6.txt");
+        let Some(data) = load_or_skip(2023, 6) else {
+            return;
+        };
 
-        let solution = day_6_part_1(data);
+        let solution = day_6_part_1(&data).unwrap();
         println!("2023.6.1: {solution}");
     }
 
     #[test]
     fn test_day_6_part_2() {
-        let data = include_str!("../../data/aoc_2023/day_6.txt");
+        let Some(data) = load_or_skip(2023, 6) else {
+            return;
+        };
 
-        let solution = day_6_part_2(data);
+        let solution = day_6_part_2(&data).unwrap();
         println!("2023.6.2: {solution}");
     }
+
+    #[test]
+    fn test_day_6_winning_holds() {
+        let count = |time, distance| Race::new(time, distance).winning_combo();
+        // 10 * 20 ties the record exactly, so only 11..=19 win.
+        assert_eq!(count(30, 200), 9);
+        assert_eq!(count(4, 4), 0);
+        assert_eq!(count(4, 3), 1);
+        assert_eq!(count(3, 100), 0);
+        assert_eq!(count(u64::MAX, u64::MAX), u64::MAX - 3);
+        assert_eq!(
+            day_6_part_2("Time: 99999999999 99999999999\nDistance: 1 0\n").unwrap(),
+            "9999999999999999999998".parse().unwrap()
+        );
+        // The closed form agrees with searching for the ends of the winning range.
+        for time in 0..40 {
+            for distance in 0..450 {
+                let race = Race::new(time, distance);
+                let searched = race
+                    .winning_holds()
+                    .map_or(0, |holds| holds.end() - holds.start() + 1);
+                assert_eq!(race.winning_combo(), searched, "{race:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_day_6_race_physics() {
+        let race = Race::new(30, 200);
+        assert_eq!(race.winning_holds(), Some(11..=19));
+        assert_eq!(race.optimal_hold(), (15, 225));
+        assert_eq!(race.with_max_hold(12).winning_holds(), Some(11..=12));
+        assert_eq!(race.with_max_hold(10).winning_holds(), None);
+        // Charging stops at 12 mm/ms, so holding past 12 ms only wastes time.
+        let capped = race.with_max_speed(12);
+        assert_eq!(capped.optimal_hold(), (12, 216));
+        assert_eq!(capped.winning_holds(), Some(11..=13));
+        let fast = Race::new(7, 9).with_acceleration(2).with_start_speed(1);
+        assert_eq!(fast.optimal_hold(), (3, 28));
+        assert_eq!(fast.winning_holds(), Some(1..=6));
+        assert_eq!(
+            Race::new(7, 9)
+                .with_acceleration(0)
+                .with_start_speed(2)
+                .winning_holds(),
+            Some(0..=2)
+        );
+
+        // Every variant agrees with trying each hold.
+        for (time, distance, acceleration, start_speed, max_hold, max_speed) in [
+            (20, 30, 1, 0, None, None),
+            (20, 50, 3, 2, Some(9), None),
+            (25, 60, 2, 1, None, Some(9)),
+            (25, 60, 2, 10, Some(4), Some(9)),
+            (9, 0, 0, 0, None, None),
+        ] {
+            let race = Race {
+                time,
+                distance,
+                acceleration,
+                start_speed,
+                max_hold,
+                max_speed,
+            };
+            let winners = (0..=time)
+                .filter(|&hold| race.travelled(hold).is_some_and(|d| d > distance.into()))
+                .collect::<Vec<_>>();
+            let holds = race.winning_holds().map_or(vec![], |holds| holds.collect());
+            assert_eq!(holds, winners, "{race:?}");
+            let best = (0..=time)
+                .filter_map(|hold| Some((hold, race.travelled(hold)?)))
+                .max_by_key(|&(hold, travelled)| (travelled, Reverse(hold)));
+            assert_eq!(Some(race.optimal_hold()), best, "{race:?}");
+        }
+    }
+
+    #[test]
+    fn test_day_6_part_1_example() {
+        assert_eq!(day_6_part_1(EXAMPLE).unwrap(), 288);
+    }
+
+    #[test]
+    fn test_day_6_part_2_example() {
+        assert_eq!(day_6_part_2(EXAMPLE).unwrap(), 71503.into());
+    }
 }
@@ -1,6 +1,17 @@
-mod day_1;
-mod day_2;
-mod day_3;
-mod day_4;
-mod day_5;
-mod day_6;
+use crate::solution::Puzzle;
+
+pub mod day_1;
+pub mod day_2;
+pub mod day_3;
+pub mod day_4;
+pub mod day_5;
+pub mod day_6;
+
+pub(crate) const PUZZLES: &[Puzzle] = &[
+    Puzzle::new::<day_1::Day1>(2023, 1),
+    Puzzle::new::<day_2::Day2>(2023, 2),
+    Puzzle::new::<day_3::Day3>(2023, 3),
+    Puzzle::new::<day_4::Day4>(2023, 4),
+    Puzzle::new::<day_5::Day5>(2023, 5),
+    Puzzle::new::<day_6::Day6>(2023, 6),
+];
@@ -0,0 +1,181 @@
+use std::{fmt::Write, time::Duration};
+
+use serde::{Serialize, Serializer};
+
+use crate::{exec::Mode, Answer, AocError, Part, Puzzle};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Stats {
+    #[serde(serialize_with = "nanos")]
+    pub min: Duration,
+    #[serde(serialize_with = "nanos")]
+    pub median: Duration,
+    #[serde(serialize_with = "nanos")]
+    pub mean: Duration,
+}
+
+impl Stats {
+    /// `samples` must not be empty.
+    pub fn new(samples: &mut [Duration]) -> Self {
+        samples.sort_unstable();
+        let len = samples.len();
+        let median = if len.is_multiple_of(2) {
+            (samples[len / 2 - 1] + samples[len / 2]) / 2
+        } else {
+            samples[len / 2]
+        };
+        Self {
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / len as u32,
+        }
+    }
+}
+
+fn display<S: Serializer>(mode: &Mode, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(mode)
+}
+
+fn nanos<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_nanos())
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Measurement {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    #[serde(serialize_with = "display")]
+    pub mode: Mode,
+    pub runs: u32,
+    pub answer: Answer,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Runs `part` of `puzzle` `runs` times (at least once), timing parse and solve separately.
+pub fn bench(
+    puzzle: &Puzzle,
+    data: &str,
+    part: Part,
+    mode: Mode,
+    runs: u32,
+) -> Result<Measurement, AocError> {
+    let runs = runs.max(1);
+    let mut parse = Vec::with_capacity(runs as usize);
+    let mut solve = Vec::with_capacity(runs as usize);
+    let mut answer = None;
+    for _ in 0..runs {
+        let timed = mode.install(|| puzzle.timed(data, part))?;
+        parse.push(timed.parse);
+        solve.push(timed.solve);
+        answer = Some(timed.answer);
+    }
+    Ok(Measurement {
+        year: puzzle.year,
+        day: puzzle.day,
+        part: part.into(),
+        mode,
+        runs,
+        answer: answer.expect("runs is at least one"),
+        parse: Stats::new(&mut parse),
+        solve: Stats::new(&mut solve),
+    })
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{nanos}ns")
+    } else if nanos < 1_000_000 {
+        format!("{:.2}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+pub fn table(measurements: &[Measurement]) -> String {
+    let mut table = format!(
+        "{:<10} {:<10} {:>5} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}  answer\n",
+        "puzzle", "mode", "runs", "parse min", "median", "mean", "solve min", "median", "mean"
+    );
+    for m in measurements {
+        let _ = writeln!(
+            table,
+            "{:<10} {:<10} {:>5} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}  {}",
+            format!("{}.{}.{}", m.year, m.day, m.part),
+            m.mode.to_string(),
+            m.runs,
+            format_duration(m.parse.min),
+            format_duration(m.parse.median),
+            format_duration(m.parse.mean),
+            format_duration(m.solve.min),
+            format_duration(m.solve.median),
+            format_duration(m.solve.mean),
+            m.answer
+        );
+    }
+    table
+}
+
+pub fn json(measurements: &[Measurement]) -> String {
+    serde_json::to_string_pretty(measurements).expect("measurements always serialize")
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{exec::Mode, find, Part};
+
+    use super::{bench, format_duration, json, table, Stats};
+
+    #[test]
+    fn test_stats() {
+        let mut samples = [5, 1, 3, 2].map(Duration::from_micros);
+        let stats = Stats::new(&mut samples);
+        assert_eq!(stats.min, Duration::from_micros(1));
+        assert_eq!(
+            stats.median,
+            Duration::from_micros(2) + Duration::from_nanos(500)
+        );
+        assert_eq!(
+            stats.mean,
+            Duration::from_micros(2) + Duration::from_nanos(750)
+        );
+
+        let stats = Stats::new(&mut [7, 3, 5].map(Duration::from_millis));
+        assert_eq!(stats.median, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999ns");
+        assert_eq!(format_duration(Duration::from_nanos(1_500)), "1.50µs");
+        assert_eq!(format_duration(Duration::from_micros(2_340)), "2.34ms");
+        assert_eq!(format_duration(Duration::from_millis(12_000)), "12.00s");
+    }
+
+    #[test]
+    fn test_bench() {
+        let puzzle = find(2023, 6).unwrap();
+        let data = "Time:      7  15   30\nDistance:  9  40  200\n";
+        let measurement = bench(puzzle, data, Part::Two, Mode::Sequential, 3).unwrap();
+        assert_eq!(measurement.runs, 3);
+        assert_eq!(measurement.answer, 71503.into());
+        assert!(measurement.parse.min <= measurement.parse.median);
+
+        let measurements = [measurement];
+        assert!(table(&measurements)
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("2023.6.2"));
+        let json: serde_json::Value = serde_json::from_str(&json(&measurements)).unwrap();
+        assert_eq!(json[0]["answer"], 71503);
+        assert_eq!(json[0]["mode"], "sequential");
+        assert!(json[0]["solve"]["median"].is_u64());
+    }
+}
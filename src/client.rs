@@ -0,0 +1,367 @@
+use std::{
+    env,
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use ureq::{Agent, AgentBuilder};
+
+use crate::input;
+
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// The shortest gap a client leaves between two of its requests.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+const USER_AGENT: &str = concat!("aoc/", env!("CARGO_PKG_VERSION"));
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum ClientError {
+    NoSession {
+        path: PathBuf,
+    },
+    Status {
+        url: String,
+        status: u16,
+        body: String,
+    },
+    /// The server asked us to slow down (HTTP 429).
+    RateLimited {
+        url: String,
+        retry_after: Option<Duration>,
+    },
+    Transport {
+        url: String,
+        reason: String,
+    },
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::NoSession { path } => write!(
+                f,
+                "no session token, set {SESSION_VAR} or write it to {}",
+                path.display()
+            ),
+            ClientError::Status { url, status, body } => {
+                write!(f, "{url}: HTTP {status}")?;
+                match body.lines().next().map(str::trim) {
+                    Some(line) if !line.is_empty() => write!(f, " ({line})"),
+                    _ => Ok(()),
+                }
+            }
+            ClientError::RateLimited {
+                url,
+                retry_after: Some(retry_after),
+            } => write!(
+                f,
+                "{url}: rate limited, retry in {}s",
+                retry_after.as_secs()
+            ),
+            ClientError::RateLimited { url, .. } => write!(f, "{url}: rate limited"),
+            ClientError::Transport { url, reason } => write!(f, "{url}: {reason}"),
+            ClientError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+        }
+    }
+}
+
+impl Error for ClientError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ClientError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// An authenticated Advent of Code client. Requests from one client are spaced at least
+/// `interval` apart.
+pub struct Client {
+    agent: Agent,
+    base_url: String,
+    session: String,
+    interval: Duration,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Self {
+            agent: AgentBuilder::new()
+                .timeout(TIMEOUT)
+                .user_agent(USER_AGENT)
+                .build(),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.trim().to_owned(),
+            interval: DEFAULT_INTERVAL,
+            last_request: Mutex::new(None),
+        }
+    }
+
+    /// Reads the base URL from `$AOC_BASE_URL` and the session token from `$AOC_SESSION`,
+    /// falling back to adventofcode.com and `<data dir>/session`.
+    pub fn from_env() -> Result<Self, ClientError> {
+        let base_url = env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_owned());
+        let session = match env::var(SESSION_VAR) {
+            Ok(session) if !session.trim().is_empty() => session,
+            _ => load_session(&session_path(&input::data_dir()))?,
+        };
+        Ok(Self::new(&base_url, &session))
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// `path` is relative to the base URL, e.g. `/2023/day/1/input`.
+    pub fn get(&self, path: &str) -> Result<String, ClientError> {
+        self.send("GET", path, None)
+    }
+
+    pub fn post_form(&self, path: &str, form: &[(&str, &str)]) -> Result<String, ClientError> {
+        self.send("POST", path, Some(form))
+    }
+
+    fn send(
+        &self,
+        method: &str,
+        path: &str,
+        form: Option<&[(&str, &str)]>,
+    ) -> Result<String, ClientError> {
+        let url = format!("{}{path}", self.base_url);
+        let request = self
+            .agent
+            .request(method, &url)
+            .set("Cookie", &format!("session={}", self.session));
+        self.throttle();
+        let result = match form {
+            Some(form) => request.send_form(form),
+            None => request.call(),
+        };
+        let response = match result {
+            Ok(response) => response,
+            Err(ureq::Error::Status(429, response)) => {
+                return Err(ClientError::RateLimited {
+                    retry_after: response
+                        .header("Retry-After")
+                        .and_then(|secs| secs.trim().parse().ok())
+                        .map(Duration::from_secs),
+                    url,
+                })
+            }
+            Err(ureq::Error::Status(status, response)) => {
+                return Err(ClientError::Status {
+                    body: response.into_string().unwrap_or_default(),
+                    url,
+                    status,
+                })
+            }
+            Err(ureq::Error::Transport(transport)) => {
+                return Err(ClientError::Transport {
+                    reason: match transport.message() {
+                        Some(message) => format!("{}: {message}", transport.kind()),
+                        None => transport.kind().to_string(),
+                    },
+                    url,
+                })
+            }
+        };
+        response
+            .into_string()
+            .map_err(|err| ClientError::Transport {
+                url,
+                reason: err.to_string(),
+            })
+    }
+
+    /// Waits until `interval` has passed since the previous request.
+    fn throttle(&self) {
+        let mut last_request = self
+            .last_request
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(last_request) = *last_request {
+            if let Some(wait) = self.interval.checked_sub(last_request.elapsed()) {
+                thread::sleep(wait);
+            }
+        }
+        *last_request = Some(Instant::now());
+    }
+}
+
+pub fn session_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("session")
+}
+
+pub fn load_session(path: &Path) -> Result<String, ClientError> {
+    match fs::read_to_string(path) {
+        Ok(session) if !session.trim().is_empty() => Ok(session.trim().to_owned()),
+        Ok(_) => Err(ClientError::NoSession {
+            path: path.to_owned(),
+        }),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Err(ClientError::NoSession {
+            path: path.to_owned(),
+        }),
+        Err(source) => Err(ClientError::Io {
+            path: path.to_owned(),
+            source,
+        }),
+    }
+}
+
+/// A one-shot HTTP server on a local port for exercising clients without a network.
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    pub(crate) fn response(status: u16, headers: &[(&str, &str)], body: &str) -> String {
+        let mut response = format!(
+            "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+            body.len()
+        );
+        for (name, value) in headers {
+            response.push_str(&format!("{name}: {value}\r\n"));
+        }
+        response + "\r\n" + body
+    }
+
+    /// Serves `responses` in order, one per connection. The handle yields the raw requests.
+    pub(crate) fn serve(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|response| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut request = String::new();
+                    let mut length = 0;
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        if let Some((name, value)) = line.split_once(':') {
+                            if name.eq_ignore_ascii_case("content-length") {
+                                length = value.trim().parse().unwrap();
+                            }
+                        }
+                        request.push_str(&line);
+                        if line.trim().is_empty() {
+                            break;
+                        }
+                    }
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).unwrap();
+                    request.push_str(&String::from_utf8(body).unwrap());
+                    stream.write_all(response.as_bytes()).unwrap();
+                    request
+                })
+                .collect()
+        });
+        (base_url, handle)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        env, fs,
+        time::{Duration, Instant},
+    };
+
+    use super::{load_session, mock, Client, ClientError};
+
+    #[test]
+    fn test_get_sends_session() {
+        let (base_url, server) = mock::serve(vec![mock::response(200, &[], "1abc2\n")]);
+        let client = Client::new(&format!("{base_url}/"), "secret\n");
+        assert_eq!(client.get("/2023/day/1/input").unwrap(), "1abc2\n");
+        let request = &server.join().unwrap()[0];
+        assert!(request.starts_with("GET /2023/day/1/input HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=secret\r\n"));
+        assert!(request.to_lowercase().contains("user-agent: aoc/"));
+    }
+
+    #[test]
+    fn test_post_form() {
+        let (base_url, server) = mock::serve(vec![mock::response(200, &[], "ok")]);
+        let client = Client::new(&base_url, "secret");
+        assert_eq!(
+            client
+                .post_form("/2023/day/1/answer", &[("level", "1"), ("answer", "142")])
+                .unwrap(),
+            "ok"
+        );
+        let request = &server.join().unwrap()[0];
+        assert!(request.starts_with("POST /2023/day/1/answer HTTP/1.1\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=1&answer=142"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let (base_url, server) = mock::serve(vec![
+            mock::response(404, &[], "Not found\n"),
+            mock::response(429, &[("Retry-After", "30")], ""),
+        ]);
+        let client = Client::new(&base_url, "secret").with_interval(Duration::ZERO);
+        let err = client.get("/2023/day/26/input").unwrap_err();
+        assert!(matches!(err, ClientError::Status { status: 404, .. }));
+        assert!(err
+            .to_string()
+            .ends_with("/2023/day/26/input: HTTP 404 (Not found)"));
+        assert!(matches!(
+            client.get("/2023/day/1/input"),
+            Err(ClientError::RateLimited {
+                retry_after: Some(retry_after),
+                ..
+            }) if retry_after == Duration::from_secs(30)
+        ));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_throttle() {
+        let (base_url, server) = mock::serve(vec![
+            mock::response(200, &[], "1"),
+            mock::response(200, &[], "2"),
+        ]);
+        let client = Client::new(&base_url, "secret").with_interval(Duration::from_millis(200));
+        let start = Instant::now();
+        client.get("/1").unwrap();
+        client.get("/2").unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_load_session() {
+        let path = env::temp_dir().join("aoc-client-test-session");
+        fs::write(&path, "  secret\n").unwrap();
+        assert_eq!(load_session(&path).unwrap(), "secret");
+        fs::write(&path, "\n").unwrap();
+        assert!(matches!(
+            load_session(&path),
+            Err(ClientError::NoSession { .. })
+        ));
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+use std::{error::Error, fmt};
+
+use crate::{input::InputError, parse::TokenError, Part};
+
+#[derive(Debug)]
+pub enum AocError {
+    Input(InputError),
+    Parse(ParseError),
+    UnknownPuzzle {
+        year: u16,
+        day: u8,
+    },
+    /// The input parsed but the puzzle cannot be answered from it.
+    Unsolvable {
+        year: u16,
+        day: u8,
+        part: Part,
+        reason: String,
+    },
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocError::Input(err) => err.fmt(f),
+            AocError::Parse(err) => err.fmt(f),
+            AocError::UnknownPuzzle { year, day } => {
+                write!(f, "{year}.{day}: no solution registered")
+            }
+            AocError::Unsolvable {
+                year,
+                day,
+                part,
+                reason,
+            } => write!(f, "{year}.{day}.{part}: {reason}"),
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Input(err) => Some(err),
+            AocError::Parse(err) => Some(err),
+            AocError::UnknownPuzzle { .. } | AocError::Unsolvable { .. } => None,
+        }
+    }
+}
+
+impl From<InputError> for AocError {
+    fn from(err: InputError) -> Self {
+        AocError::Input(err)
+    }
+}
+
+impl From<ParseError> for AocError {
+    fn from(err: ParseError) -> Self {
+        AocError::Parse(err)
+    }
+}
+
+/// A malformed input, located by 1-based line and column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub year: u16,
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{} line {}, column {}: {}",
+            self.year, self.day, self.line, self.column, self.reason
+        )?;
+        if !self.text.is_empty() {
+            write!(f, " (found `{}`)", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PuzzleId {
+    pub year: u16,
+    pub day: u8,
+}
+
+impl PuzzleId {
+    pub const fn new(year: u16, day: u8) -> Self {
+        Self { year, day }
+    }
+
+    /// `idx` is the 0-based line index and `token` a subslice of `line`, which gives the column.
+    /// A `token` outside `line` is reported at column 1.
+    pub fn parse_error(
+        self,
+        idx: usize,
+        line: &str,
+        token: &str,
+        reason: impl Into<String>,
+    ) -> ParseError {
+        let offset = (token.as_ptr() as usize)
+            .checked_sub(line.as_ptr() as usize)
+            .filter(|offset| offset + token.len() <= line.len())
+            .unwrap_or(0);
+        ParseError {
+            year: self.year,
+            day: self.day,
+            line: idx + 1,
+            column: line[..offset].chars().count() + 1,
+            text: token.to_owned(),
+            reason: reason.into(),
+        }
+    }
+
+    /// A [`TokenError`] found on the line at 0-based index `idx`.
+    pub fn invalid(self, idx: usize, line: &str, err: TokenError<'_>) -> ParseError {
+        self.parse_error(idx, line, err.token(), err.to_string())
+    }
+
+    /// A section missing from the input, reported just past its last line.
+    pub fn missing(self, data: &str, reason: impl Into<String>) -> ParseError {
+        self.parse_error(data.lines().count(), "", "", reason)
+    }
+
+    pub fn unsolvable(self, part: Part, reason: impl Into<String>) -> AocError {
+        AocError::Unsolvable {
+            year: self.year,
+            day: self.day,
+            part,
+            reason: reason.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PuzzleId;
+
+    const ID: PuzzleId = PuzzleId::new(2023, 2);
+
+    #[test]
+    fn test_parse_error_location() {
+        let line = "Game 1: 3 blue, x red";
+        let token = &line[16..17];
+        let err = ID.parse_error(4, line, token, "expected a number");
+        assert_eq!((err.line, err.column), (5, 17));
+        assert_eq!(err.text, "x");
+        assert_eq!(
+            err.to_string(),
+            "2023.2 line 5, column 17: expected a number (found `x`)"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_foreign_token() {
+        let err = ID.parse_error(0, "Game 1", "Game", "unexpected");
+        assert_eq!(err.column, 1);
+    }
+
+    #[test]
+    fn test_missing_section() {
+        let err = ID.missing("a\nb\n", "missing `map:` section");
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(
+            err.to_string(),
+            "2023.2 line 3, column 1: missing `map:` section"
+        );
+    }
+}
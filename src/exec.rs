@@ -0,0 +1,168 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    num::NonZeroUsize,
+    str::FromStr,
+    sync::{Mutex, PoisonError},
+};
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::{Answer, AocError, Part, Puzzle};
+
+/// How solvers run their rayon iterators.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// On rayon's global pool.
+    #[default]
+    Parallel,
+    /// On a single-threaded pool, so every parallel iterator runs in order on one thread.
+    Sequential,
+    /// On a dedicated pool with this many threads.
+    Threads(NonZeroUsize),
+}
+
+impl Mode {
+    /// The pool this mode runs on, `None` for rayon's global pool.
+    pub fn pool(self) -> Option<&'static ThreadPool> {
+        match self {
+            Mode::Parallel => None,
+            Mode::Sequential => Some(pool(NonZeroUsize::MIN)),
+            Mode::Threads(threads) => Some(pool(threads)),
+        }
+    }
+
+    pub fn install<R: Send>(self, op: impl FnOnce() -> R + Send) -> R {
+        match self.pool() {
+            Some(pool) => pool.install(op),
+            None => op(),
+        }
+    }
+}
+
+/// Pools are built on first use and kept for the life of the process.
+fn pool(threads: NonZeroUsize) -> &'static ThreadPool {
+    static POOLS: Mutex<BTreeMap<NonZeroUsize, &'static ThreadPool>> = Mutex::new(BTreeMap::new());
+    let mut pools = POOLS.lock().unwrap_or_else(PoisonError::into_inner);
+    pools.entry(threads).or_insert_with(|| {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads.get())
+            .thread_name(move |idx| format!("aoc-{threads}-{idx}"))
+            .build()
+            .expect("cannot start a solver thread pool");
+        Box::leak(Box::new(pool))
+    })
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Parallel => write!(f, "parallel"),
+            Mode::Sequential => write!(f, "sequential"),
+            Mode::Threads(threads) => write!(f, "threads={threads}"),
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    /// `parallel`, `sequential`, or a thread count as `N` or `threads=N`.
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "parallel" => Ok(Mode::Parallel),
+            "sequential" => Ok(Mode::Sequential),
+            _ => match mode.strip_prefix("threads=").unwrap_or(mode).parse() {
+                Ok(threads) => Ok(Mode::Threads(threads)),
+                Err(_) => Err(format!(
+                    "unknown mode `{mode}`, expected `parallel`, `sequential` or a thread count"
+                )),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Differential {
+    Agree(Answer),
+    /// `parallel` holds every distinct answer seen over the parallel rounds.
+    Disagree {
+        sequential: Answer,
+        parallel: Vec<Answer>,
+    },
+}
+
+/// Solves `part` once sequentially and `rounds` times in parallel, comparing the answers.
+pub fn differential(
+    puzzle: &Puzzle,
+    data: &str,
+    part: Part,
+    rounds: u32,
+) -> Result<Differential, AocError> {
+    let sequential = Mode::Sequential.install(|| puzzle.run(data, part))?;
+    let mut parallel: Vec<Answer> = Vec::new();
+    for _ in 0..rounds.max(1) {
+        let answer = Mode::Parallel.install(|| puzzle.run(data, part))?;
+        if !parallel.contains(&answer) {
+            parallel.push(answer);
+        }
+    }
+    Ok(if parallel == [sequential.clone()] {
+        Differential::Agree(sequential)
+    } else {
+        Differential::Disagree {
+            sequential,
+            parallel,
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::NonZeroUsize;
+
+    use crate::{find, Part};
+
+    use super::{differential, Differential, Mode};
+
+    fn threads(threads: usize) -> Mode {
+        Mode::Threads(NonZeroUsize::new(threads).unwrap())
+    }
+
+    #[test]
+    fn test_mode_install() {
+        assert_eq!(Mode::Sequential.install(rayon::current_num_threads), 1);
+        assert_eq!(threads(3).install(rayon::current_num_threads), 3);
+        assert_eq!(
+            Mode::Parallel.install(rayon::current_num_threads),
+            rayon::current_num_threads()
+        );
+        assert!(std::ptr::eq(
+            threads(3).pool().unwrap(),
+            threads(3).pool().unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_mode_from_str() {
+        assert_eq!("sequential".parse(), Ok(Mode::Sequential));
+        assert_eq!("parallel".parse(), Ok(Mode::Parallel));
+        assert_eq!("4".parse(), Ok(threads(4)));
+        assert_eq!("threads=2".parse(), Ok(threads(2)));
+        assert_eq!(threads(2).to_string().parse(), Ok(threads(2)));
+        assert!("0".parse::<Mode>().is_err());
+        assert!("threads=0".parse::<Mode>().is_err());
+        assert!("both".parse::<Mode>().is_err());
+    }
+
+    #[test]
+    fn test_differential() {
+        let puzzle = find(2023, 3).unwrap();
+        let data = "467..114..\n...*......\n..35..633.\n......#...\n617*......\n\
+                    .....+.58.\n..592.....\n......755.\n...$.*....\n.664.598..\n";
+        assert_eq!(
+            differential(puzzle, data, Part::Two, 4).unwrap(),
+            Differential::Agree(467835.into())
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    client::{Client, ClientError},
+    input,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fetched {
+    pub path: PathBuf,
+    /// `false` when the input was already cached.
+    pub downloaded: bool,
+}
+
+pub fn input_url(year: u16, day: u8) -> String {
+    format!("/{year}/day/{day}/input")
+}
+
+/// Makes sure the input for `year`/`day` is cached under `data_dir`, downloading it if the
+/// cached file is missing or empty, or if `refresh` is set.
+pub fn fetch(
+    client: &Client,
+    data_dir: &Path,
+    year: u16,
+    day: u8,
+    refresh: bool,
+) -> Result<Fetched, ClientError> {
+    let path = input::input_path(data_dir, year, day);
+    let cached = fs::metadata(&path).is_ok_and(|metadata| metadata.len() > 0);
+    if cached && !refresh {
+        return Ok(Fetched {
+            path,
+            downloaded: false,
+        });
+    }
+
+    let data = client.get(&input_url(year, day))?;
+    let io_error = |path: &Path| {
+        let path = path.to_owned();
+        move |source| ClientError::Io { path, source }
+    };
+    let dir = input::year_dir(data_dir, year);
+    fs::create_dir_all(&dir).map_err(io_error(&dir))?;
+    // Write then rename, so an interrupted download never leaves a truncated input behind.
+    let partial = path.with_extension("txt.part");
+    fs::write(&partial, data).map_err(io_error(&partial))?;
+    fs::rename(&partial, &path).map_err(io_error(&path))?;
+    Ok(Fetched {
+        path,
+        downloaded: true,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, time::Duration};
+
+    use crate::client::{mock, Client, ClientError};
+
+    use super::{fetch, Fetched};
+
+    #[test]
+    fn test_fetch_caches() {
+        let data_dir = env::temp_dir().join("aoc-fetch-test-cache");
+        let _ = fs::remove_dir_all(&data_dir);
+        let (base_url, server) = mock::serve(vec![
+            mock::response(200, &[], "Time: 7\n"),
+            mock::response(200, &[], "Time: 8\n"),
+        ]);
+        let client = Client::new(&base_url, "secret").with_interval(Duration::ZERO);
+        let path = data_dir.join("aoc_2023/day_6.txt");
+
+        let downloaded = Fetched {
+            path: path.clone(),
+            downloaded: true,
+        };
+        assert_eq!(
+            fetch(&client, &data_dir, 2023, 6, false).unwrap(),
+            downloaded
+        );
+        assert_eq!(
+            fetch(&client, &data_dir, 2023, 6, false).unwrap(),
+            Fetched {
+                path: path.clone(),
+                downloaded: false,
+            }
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Time: 7\n");
+
+        assert_eq!(
+            fetch(&client, &data_dir, 2023, 6, true).unwrap(),
+            downloaded
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Time: 8\n");
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].starts_with("GET /2023/day/6/input "));
+        fs::remove_dir_all(data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_error_leaves_no_file() {
+        let data_dir = env::temp_dir().join("aoc-fetch-test-error");
+        let _ = fs::remove_dir_all(&data_dir);
+        let (base_url, server) = mock::serve(vec![mock::response(
+            400,
+            &[],
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+        )]);
+        let client = Client::new(&base_url, "expired");
+        assert!(matches!(
+            fetch(&client, &data_dir, 2023, 1, false),
+            Err(ClientError::Status { status: 400, .. })
+        ));
+        assert!(!data_dir.join("aoc_2023/day_1.txt").exists());
+        server.join().unwrap();
+    }
+}
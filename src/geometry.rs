@@ -0,0 +1,238 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position or displacement on a grid: `x` is the column and `y` the row, growing down from
+/// the top-left, so [`Direction::N`] is negative `y`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point2 {
+    pub const ORIGIN: Self = Self::new(0, 0);
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// The point at `idx` in a row-major layout `width` cells wide.
+    pub fn from_index(idx: usize, width: usize) -> Self {
+        Self::new((idx % width) as i64, (idx / width) as i64)
+    }
+
+    /// The row-major index of the point in a `width` by `height` grid, if it is inside.
+    pub fn to_index(self, width: usize, height: usize) -> Option<usize> {
+        let x = usize::try_from(self.x).ok().filter(|&x| x < width)?;
+        let y = usize::try_from(self.y).ok().filter(|&y| y < height)?;
+        Some(y * width + x)
+    }
+
+    pub fn manhattan(self, other: Self) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Distance counting diagonal steps as one, like a king's moves.
+    pub fn chebyshev(self, other: Self) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.delta()
+    }
+}
+
+impl Point3 {
+    pub const ORIGIN: Self = Self::new(0, 0, 0);
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn manhattan(self, other: Self) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    pub fn chebyshev(self, other: Self) -> u64 {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+}
+
+macro_rules! ops {
+    ($point:ident { $($axis:ident),* }) => {
+        impl Add for $point {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self { $($axis: self.$axis + other.$axis),* }
+            }
+        }
+
+        impl Sub for $point {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self { $($axis: self.$axis - other.$axis),* }
+            }
+        }
+
+        impl Neg for $point {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($axis: -self.$axis),* }
+            }
+        }
+
+        impl Mul<i64> for $point {
+            type Output = Self;
+
+            fn mul(self, scale: i64) -> Self {
+                Self { $($axis: self.$axis * scale),* }
+            }
+        }
+
+        impl AddAssign for $point {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $point {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+ops!(Point2 { x, y });
+ops!(Point3 { x, y, z });
+
+/// A compass direction on a grid, with north up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction {
+    /// All eight, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::N,
+        Direction::NE,
+        Direction::E,
+        Direction::SE,
+        Direction::S,
+        Direction::SW,
+        Direction::W,
+        Direction::NW,
+    ];
+    /// North, east, south and west.
+    pub const ORTHOGONAL: [Direction; 4] = [Direction::N, Direction::E, Direction::S, Direction::W];
+
+    /// One step in this direction.
+    pub const fn delta(self) -> Point2 {
+        match self {
+            Direction::N => Point2::new(0, -1),
+            Direction::NE => Point2::new(1, -1),
+            Direction::E => Point2::new(1, 0),
+            Direction::SE => Point2::new(1, 1),
+            Direction::S => Point2::new(0, 1),
+            Direction::SW => Point2::new(-1, 1),
+            Direction::W => Point2::new(-1, 0),
+            Direction::NW => Point2::new(-1, -1),
+        }
+    }
+
+    /// Turns clockwise by `eighths` of a full turn; negative turns anticlockwise.
+    pub fn rotate(self, eighths: i32) -> Self {
+        Self::ALL[(self as i32 + eighths).rem_euclid(8) as usize]
+    }
+
+    pub fn right(self) -> Self {
+        self.rotate(2)
+    }
+
+    pub fn left(self) -> Self {
+        self.rotate(-2)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as u8 % 2 == 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Direction, Point2, Point3};
+
+    #[test]
+    fn test_arithmetic() {
+        let mut point = Point2::new(3, -2);
+        assert_eq!(point + Point2::new(1, 1), Point2::new(4, -1));
+        assert_eq!(point - Point2::new(1, 1), Point2::new(2, -3));
+        assert_eq!(-point * 2, Point2::new(-6, 4));
+        point += Direction::NW.delta();
+        assert_eq!(point, Point2::new(2, -3));
+        point -= point;
+        assert_eq!(point, Point2::ORIGIN);
+        assert_eq!(
+            Point3::new(1, 2, 3) + Point3::new(1, 1, 1) * 2,
+            Point3::new(3, 4, 5)
+        );
+    }
+
+    #[test]
+    fn test_distances() {
+        let (a, b) = (Point2::new(1, -2), Point2::new(-3, 5));
+        assert_eq!((a.manhattan(b), a.chebyshev(b)), (11, 7));
+        let (a, b) = (Point3::new(0, 0, 0), Point3::new(-1, 4, 2));
+        assert_eq!((a.manhattan(b), a.chebyshev(b)), (7, 4));
+        assert_eq!(
+            Point2::new(i64::MIN, 0).manhattan(Point2::new(i64::MAX, 0)),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction::N.right(), Direction::E);
+        assert_eq!(Direction::N.left(), Direction::W);
+        assert_eq!(Direction::SW.opposite(), Direction::NE);
+        assert_eq!(Direction::NW.rotate(1), Direction::N);
+        assert_eq!(Direction::N.rotate(-9), Direction::NW);
+        assert!(Direction::SE.is_diagonal() && !Direction::S.is_diagonal());
+        for direction in Direction::ALL {
+            assert_eq!(direction.opposite().delta(), -direction.delta());
+        }
+        assert_eq!(Point2::new(2, 2).step(Direction::N), Point2::new(2, 1));
+    }
+
+    #[test]
+    fn test_indices() {
+        assert_eq!(Point2::new(2, 1).to_index(3, 2), Some(5));
+        assert_eq!(Point2::from_index(5, 3), Point2::new(2, 1));
+        assert_eq!(Point2::new(3, 0).to_index(3, 2), None);
+        assert_eq!(Point2::new(0, -1).to_index(3, 2), None);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::parse::{Int, TokenError};
+
+/// What is left of the input after a successful parse, and what was parsed.
+pub type PResult<'a, O> = Result<(&'a str, O), TokenError<'a>>;
+
+/// A parser is any `Fn(&str) -> PResult`. Parsers that fail without matching report a
+/// [mismatch](TokenError::is_mismatch), which lets [`many`] and [`separated`] stop cleanly;
+/// any other error aborts the whole parse.
+pub trait Parser<'a, O> {
+    fn parse(&self, input: &'a str) -> PResult<'a, O>;
+
+    fn map<U>(self, f: impl Fn(O) -> U) -> impl Fn(&'a str) -> PResult<'a, U>
+    where
+        Self: Sized,
+    {
+        move |input: &'a str| {
+            let (rest, output) = self.parse(input)?;
+            Ok((rest, f(output)))
+        }
+    }
+
+    /// Reports a mismatch as `expected` instead of whatever the inner parser expected.
+    fn context(self, expected: &'static str) -> impl Fn(&'a str) -> PResult<'a, O>
+    where
+        Self: Sized,
+    {
+        move |input: &'a str| {
+            self.parse(input).map_err(|err| match err {
+                TokenError::Tag { found, .. } | TokenError::Expected { found, .. } => {
+                    TokenError::Expected { found, expected }
+                }
+                err => err,
+            })
+        }
+    }
+}
+
+impl<'a, O, F: Fn(&'a str) -> PResult<'a, O>> Parser<'a, O> for F {
+    fn parse(&self, input: &'a str) -> PResult<'a, O> {
+        self(input)
+    }
+}
+
+/// Runs `parser` over the whole of `input`, which may only have whitespace left over.
+pub fn complete<'a, O>(parser: impl Parser<'a, O>, input: &'a str) -> Result<O, TokenError<'a>> {
+    let (rest, output) = parser.parse(input)?;
+    let rest = rest.trim_start();
+    if rest.is_empty() {
+        Ok(output)
+    } else {
+        Err(expected(rest, "end of line"))
+    }
+}
+
+fn expected<'a>(input: &'a str, expected: &'static str) -> TokenError<'a> {
+    TokenError::Expected {
+        found: found(input),
+        expected,
+    }
+}
+
+/// The word (or lone symbol) at the start of `input`, to point errors at.
+fn found(input: &str) -> &str {
+    let len = input
+        .find(|c: char| !c.is_alphanumeric() && c != '-')
+        .unwrap_or(input.len());
+    match len {
+        0 => input.chars().next().map_or("", |c| &input[..c.len_utf8()]),
+        len => &input[..len],
+    }
+}
+
+pub fn tag<'a>(tag: &'static str) -> impl Fn(&'a str) -> PResult<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(tag) {
+        Some(rest) => Ok((rest, &input[..tag.len()])),
+        None => Err(TokenError::Tag {
+            found: found(input),
+            tag,
+        }),
+    }
+}
+
+/// One of `words`, as a whole word, mapped to its value.
+pub fn keyword<'a, T: Copy>(
+    words: &'static [(&'static str, T)],
+    expected: &'static str,
+) -> impl Fn(&'a str) -> PResult<'a, T> {
+    move |input: &'a str| {
+        let word = found(input);
+        words
+            .iter()
+            .find(|(keyword, _)| *keyword == word)
+            .map(|(_, value)| (&input[word.len()..], *value))
+            .ok_or(TokenError::Expected {
+                found: word,
+                expected,
+            })
+    }
+}
+
+/// An optionally signed decimal integer. Unsigned types reject a sign rather than skip it.
+pub fn int<'a, T: Int>() -> impl Fn(&'a str) -> PResult<'a, T> {
+    move |input: &'a str| {
+        let sign = usize::from(input.starts_with('-'));
+        let digits = input[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(input.len() - sign);
+        if digits == 0 {
+            return Err(expected(input, "a number"));
+        }
+        let (token, rest) = input.split_at(sign + digits);
+        if sign == 1 && !T::SIGNED {
+            return Err(TokenError::Negative(token));
+        }
+        let value = token.parse().map_err(|_| TokenError::OutOfRange(token))?;
+        Ok((rest, value))
+    }
+}
+
+/// Skips leading whitespace before `parser`.
+pub fn spaced<'a, O>(parser: impl Parser<'a, O>) -> impl Fn(&'a str) -> PResult<'a, O> {
+    move |input: &'a str| parser.parse(input.trim_start())
+}
+
+pub fn pair<'a, A, B>(
+    first: impl Parser<'a, A>,
+    second: impl Parser<'a, B>,
+) -> impl Fn(&'a str) -> PResult<'a, (A, B)> {
+    move |input: &'a str| {
+        let (rest, a) = first.parse(input)?;
+        let (rest, b) = second.parse(rest)?;
+        Ok((rest, (a, b)))
+    }
+}
+
+/// `parser`, after `prefix` whose output is dropped.
+pub fn preceded<'a, P, O>(
+    prefix: impl Parser<'a, P>,
+    parser: impl Parser<'a, O>,
+) -> impl Fn(&'a str) -> PResult<'a, O> {
+    pair(prefix, parser).map(|(_, output)| output)
+}
+
+/// Zero or more `parser`s, up to the first mismatch.
+pub fn many<'a, O>(parser: impl Parser<'a, O>) -> impl Fn(&'a str) -> PResult<'a, Vec<O>> {
+    move |mut input: &'a str| {
+        let mut outputs = Vec::new();
+        loop {
+            match parser.parse(input) {
+                // A parser that matched nothing would match nothing forever.
+                Ok((rest, output)) if rest.len() < input.len() => {
+                    outputs.push(output);
+                    input = rest;
+                }
+                Ok(_) => return Ok((input, outputs)),
+                Err(err) if err.is_mismatch() => return Ok((input, outputs)),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// One or more `parser`s separated by `separator`.
+pub fn separated<'a, S, O>(
+    parser: impl Parser<'a, O>,
+    separator: impl Parser<'a, S>,
+) -> impl Fn(&'a str) -> PResult<'a, Vec<O>> {
+    move |input: &'a str| {
+        let (mut input, first) = parser.parse(input)?;
+        let mut outputs = vec![first];
+        loop {
+            match separator.parse(input) {
+                Ok((rest, _)) => {
+                    let (rest, output) = parser.parse(rest)?;
+                    outputs.push(output);
+                    input = rest;
+                }
+                Err(err) if err.is_mismatch() => return Ok((input, outputs)),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parse::TokenError;
+
+    use super::{complete, int, keyword, many, pair, preceded, separated, spaced, tag, Parser};
+
+    #[test]
+    fn test_int() {
+        assert_eq!(int::<i8>().parse("-12 rest"), Ok((" rest", -12)));
+        assert_eq!(int::<u8>().parse("-12"), Err(TokenError::Negative("-12")));
+        assert_eq!(int::<u8>().parse("256"), Err(TokenError::OutOfRange("256")));
+        let err = int::<u8>().parse("x1").unwrap_err();
+        assert!(err.is_mismatch());
+        assert_eq!(err.to_string(), "expected a number");
+        assert_eq!(err.token(), "x1");
+    }
+
+    #[test]
+    fn test_sequences() {
+        let numbers = many(spaced(int::<u32>()));
+        let card = pair(
+            preceded(tag("Card"), spaced(int::<u32>())),
+            preceded(
+                spaced(tag(":")),
+                pair(&numbers, preceded(spaced(tag("|")), &numbers)),
+            ),
+        );
+        assert_eq!(
+            complete(&card, "Card 1: 41 48 | 83 86  6"),
+            Ok((1, (vec![41, 48], vec![83, 86, 6])))
+        );
+        let err = complete(&card, "Card 1: 41 48 | 83 x").unwrap_err();
+        assert_eq!(
+            err,
+            TokenError::Expected {
+                found: "x",
+                expected: "end of line"
+            }
+        );
+        let err = complete(&card, "Card 1: 41 99999999999 | 1").unwrap_err();
+        assert_eq!(err, TokenError::OutOfRange("99999999999"));
+        assert_eq!(
+            complete(&card, "Cart 1: 1 | 1").unwrap_err().to_string(),
+            "expected `Card`"
+        );
+    }
+
+    #[test]
+    fn test_separated_keywords() {
+        let colours = separated(
+            spaced(keyword(&[("red", 0), ("blue", 2)], "a colour")),
+            tag(","),
+        );
+        assert_eq!(complete(&colours, "red, blue,red"), Ok(vec![0, 2, 0]));
+        assert_eq!(
+            complete(&colours, "red, reddish"),
+            Err(TokenError::Expected {
+                found: "reddish",
+                expected: "a colour"
+            })
+        );
+        assert_eq!(
+            complete(colours.context("`<colour>, ...`"), "").unwrap_err(),
+            TokenError::Expected {
+                found: "",
+                expected: "`<colour>, ...`"
+            }
+        );
+        assert_eq!(
+            complete(int::<u8>().map(|n| n * 2), " 21"),
+            Err(TokenError::Expected {
+                found: " ",
+                expected: "a number"
+            })
+        );
+    }
+}
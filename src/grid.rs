@@ -0,0 +1,277 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    geometry::{Direction, Point2},
+    ParseError, PuzzleId,
+};
+
+/// A rectangle of cells stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// One cell per character, one row per line. `cell` says what it expected when it rejects
+    /// a character; rows of different lengths are an error too.
+    pub fn parse(
+        id: PuzzleId,
+        data: &str,
+        cell: impl Fn(char) -> Result<T, &'static str>,
+    ) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::with_capacity(data.len());
+        for (idx, line) in data.trim_end_matches(['\n', '\r']).lines().enumerate() {
+            let mut len = 0;
+            for (char_idx, char) in line.char_indices() {
+                let token = &line[char_idx..char_idx + char.len_utf8()];
+                cells.push(cell(char).map_err(|reason| id.parse_error(idx, line, token, reason))?);
+                len += 1;
+            }
+            match width {
+                None => width = Some(len),
+                Some(width) if width != len => {
+                    return Err(id.parse_error(
+                        idx,
+                        line,
+                        line,
+                        format!("expected {width} cells, found {len}"),
+                    ))
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point2) -> bool {
+        self.offset(point).is_some()
+    }
+
+    fn offset(&self, point: Point2) -> Option<usize> {
+        point.to_index(self.width, self.height)
+    }
+
+    pub fn get(&self, point: Point2) -> Option<&T> {
+        self.offset(point).map(|offset| &self.cells[offset])
+    }
+
+    pub fn get_mut(&mut self, point: Point2) -> Option<&mut T> {
+        self.offset(point).map(|offset| &mut self.cells[offset])
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(offset, cell)| (Point2::from_index(offset, self.width), cell))
+    }
+
+    /// The up to 4 orthogonal neighbours of `point` inside the grid, clockwise from north.
+    pub fn neighbours4(&self, point: Point2) -> impl Iterator<Item = Point2> + '_ {
+        self.neighbours(point, &Direction::ORTHOGONAL)
+    }
+
+    /// The up to 8 neighbours of `point` inside the grid, diagonals included.
+    pub fn neighbours8(&self, point: Point2) -> impl Iterator<Item = Point2> + '_ {
+        self.neighbours(point, &Direction::ALL)
+    }
+
+    fn neighbours(
+        &self,
+        point: Point2,
+        directions: &'static [Direction],
+    ) -> impl Iterator<Item = Point2> + '_ {
+        directions
+            .iter()
+            .map(move |&direction| point.step(direction))
+            .filter(|&neighbour| self.contains(neighbour))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` rejects a zero width, which only an empty grid has.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// The grid flipped over its main diagonal, so rows become columns.
+    pub fn transposed(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width: self.height,
+            height: self.width,
+            cells: self.columns().flatten().cloned().collect(),
+        }
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// The region around `start` reachable through orthogonal steps between cells where
+    /// `connected(from, to)` holds, in the order it was explored. Empty if `start` is outside.
+    pub fn flood_fill(&self, start: Point2, connected: impl Fn(&T, &T) -> bool) -> Vec<Point2> {
+        if !self.contains(start) {
+            return Vec::new();
+        }
+        let mut region = vec![start];
+        let mut seen = HashSet::from([start]);
+        let mut next = 0;
+        while let Some(&point) = region.get(next) {
+            next += 1;
+            for neighbour in self.neighbours4(point) {
+                if connected(&self[point], &self[neighbour]) && seen.insert(neighbour) {
+                    region.push(neighbour);
+                }
+            }
+        }
+        region
+    }
+}
+
+impl<T> Index<Point2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point2) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point:?} outside {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Point2> for Grid<T> {
+    fn index_mut(&mut self, point: Point2) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point:?} outside {width}x{height} grid"))
+    }
+}
+
+/// Each cell's own rendering, one line per row.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                cell.fmt(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::PuzzleId;
+
+    use crate::geometry::Point2;
+
+    use super::Grid;
+
+    const ID: PuzzleId = PuzzleId::new(2023, 0);
+
+    fn digits(data: &str) -> Grid<u32> {
+        Grid::parse(ID, data, |c| c.to_digit(10).ok_or("expected a digit")).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_index() {
+        let grid = digits("123\n456\n\n");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point2::new(2, 1)], 6);
+        assert_eq!(grid.get(Point2::new(3, 0)), None);
+        assert_eq!(grid.get(Point2::new(0, -1)), None);
+        assert_eq!(grid.to_string(), "123\n456");
+
+        let err = Grid::parse(ID, "12\n3x", |c| c.to_digit(10).ok_or("expected a digit"));
+        let err = err.unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.reason, "expected a digit");
+        let err = Grid::parse(ID, "12\n345", Ok::<_, &str>).unwrap_err();
+        assert_eq!(err.reason, "expected 2 cells, found 3");
+        assert_eq!(digits("").height(), 0);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits("123\n456\n789");
+        let centre = grid.neighbours8(Point2::new(1, 1)).map(|p| grid[p]);
+        assert_eq!(centre.collect::<Vec<_>>(), [2, 3, 6, 9, 8, 7, 4, 1]);
+        let corner = grid.neighbours4(Point2::new(0, 0)).map(|p| grid[p]);
+        assert_eq!(corner.collect::<Vec<_>>(), [2, 4]);
+        assert_eq!(grid.neighbours8(Point2::new(2, 2)).count(), 3);
+    }
+
+    #[test]
+    fn test_views() {
+        let mut grid = digits("123\n456");
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), [3, 6]);
+        assert_eq!(grid.transposed().to_string(), "14\n25\n36");
+        grid[Point2::new(0, 0)] = 9;
+        assert_eq!(grid.map(|n| n % 2).to_string(), "101\n010");
+        assert_eq!(Grid::new(2, 1, '.').to_string(), "..");
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = digits("1100\n0001\n1111");
+        let mut region = grid.flood_fill(Point2::new(0, 0), |a, b| a == b);
+        region.sort();
+        assert_eq!(region, [Point2::new(0, 0), Point2::new(1, 0)]);
+        assert_eq!(grid.flood_fill(Point2::new(2, 0), |a, b| a == b).len(), 5);
+        assert_eq!(grid.flood_fill(Point2::new(0, 2), |a, b| a == b).len(), 5);
+        assert!(grid.flood_fill(Point2::new(4, 0), |_, _| true).is_empty());
+    }
+}
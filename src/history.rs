@@ -0,0 +1,302 @@
+use std::{
+    cmp::Ordering,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input::{self, InputError},
+    Answer, Part,
+};
+
+/// What the site said about a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feedback {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, with no hint about the direction.
+    Incorrect,
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Feedback::Correct => write!(f, "correct"),
+            Feedback::TooHigh => write!(f, "too high"),
+            Feedback::TooLow => write!(f, "too low"),
+            Feedback::Incorrect => write!(f, "incorrect"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Guess {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Answer,
+    pub feedback: Feedback,
+    /// Seconds since the Unix epoch.
+    pub at: u64,
+}
+
+/// Why the history says a candidate answer is wrong.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Conflict {
+    /// The same answer was submitted and rejected.
+    Rejected(Feedback),
+    /// At or above an answer that was too high.
+    TooHigh { bound: Answer },
+    /// At or below an answer that was too low.
+    TooLow { bound: Answer },
+    /// Another answer was accepted.
+    Differs { correct: Answer },
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Conflict::Rejected(feedback) => write!(f, "was already rejected as {feedback}"),
+            Conflict::TooHigh { bound } => write!(f, "is not below {bound}, which was too high"),
+            Conflict::TooLow { bound } => write!(f, "is not above {bound}, which was too low"),
+            Conflict::Differs { correct } => {
+                write!(f, "differs from the accepted answer {correct}")
+            }
+        }
+    }
+}
+
+/// The range a numeric answer must fall in, implied by earlier too high and too low verdicts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bounds {
+    /// The largest answer that was too low.
+    pub lower: Option<Answer>,
+    /// The smallest answer that was too high.
+    pub upper: Option<Answer>,
+}
+
+/// Every answer submitted for one year, kept in `<data dir>/aoc_<year>/history.json`.
+#[derive(Debug)]
+pub struct History {
+    year: u16,
+    path: PathBuf,
+    guesses: Vec<Guess>,
+}
+
+impl History {
+    pub fn path(data_dir: &Path, year: u16) -> PathBuf {
+        input::year_dir(data_dir, year).join("history.json")
+    }
+
+    /// An empty history when the file does not exist yet.
+    pub fn load(data_dir: &Path, year: u16) -> Result<Self, InputError> {
+        let path = Self::path(data_dir, year);
+        let guesses = match fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data).map_err(|err| InputError::Invalid {
+                path: path.clone(),
+                reason: err.to_string(),
+            })?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(source) => {
+                return Err(InputError::Io {
+                    path: Some(path),
+                    source,
+                })
+            }
+        };
+        Ok(Self {
+            year,
+            path,
+            guesses,
+        })
+    }
+
+    pub fn save(&self) -> Result<(), InputError> {
+        let io_error = |source| InputError::Io {
+            path: Some(self.path.clone()),
+            source,
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let data = serde_json::to_string_pretty(&self.guesses).expect("guesses always serialize");
+        fs::write(&self.path, data + "\n").map_err(io_error)
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn guesses(&self, day: u8, part: Part) -> impl Iterator<Item = &Guess> {
+        self.guesses
+            .iter()
+            .filter(move |guess| guess.day == day && guess.part == part)
+    }
+
+    /// The earlier submission of this same answer, if any.
+    pub fn find(&self, day: u8, part: Part, answer: &Answer) -> Option<&Guess> {
+        self.guesses(day, part)
+            .find(|guess| guess.answer == *answer)
+    }
+
+    pub fn correct(&self, day: u8, part: Part) -> Option<&Answer> {
+        self.guesses(day, part)
+            .find(|guess| guess.feedback == Feedback::Correct)
+            .map(|guess| &guess.answer)
+    }
+
+    pub fn bounds(&self, day: u8, part: Part) -> Bounds {
+        let mut bounds = Bounds::default();
+        for guess in self.guesses(day, part) {
+            let Some(value) = guess.answer.to_bigint() else {
+                continue;
+            };
+            let (bound, tighter) = match guess.feedback {
+                Feedback::TooLow => (&mut bounds.lower, Ordering::Greater),
+                Feedback::TooHigh => (&mut bounds.upper, Ordering::Less),
+                Feedback::Correct | Feedback::Incorrect => continue,
+            };
+            if bound
+                .as_ref()
+                .and_then(Answer::to_bigint)
+                .is_none_or(|current| value.cmp(&current) == tighter)
+            {
+                *bound = Some(guess.answer.clone());
+            }
+        }
+        bounds
+    }
+
+    /// Checks a candidate answer against every earlier verdict for its part.
+    pub fn check(&self, day: u8, part: Part, answer: &Answer) -> Option<Conflict> {
+        if let Some(correct) = self.correct(day, part) {
+            return (correct != answer).then(|| Conflict::Differs {
+                correct: correct.clone(),
+            });
+        }
+        if let Some(guess) = self.find(day, part, answer) {
+            return Some(Conflict::Rejected(guess.feedback));
+        }
+        let value = answer.to_bigint()?;
+        let Bounds { lower, upper } = self.bounds(day, part);
+        if let Some(bound) =
+            upper.filter(|bound| bound.to_bigint().is_some_and(|bound| value >= bound))
+        {
+            return Some(Conflict::TooHigh { bound });
+        }
+        lower
+            .filter(|bound| bound.to_bigint().is_some_and(|bound| value <= bound))
+            .map(|bound| Conflict::TooLow { bound })
+    }
+
+    pub fn record(&mut self, day: u8, part: Part, answer: Answer, feedback: Feedback) {
+        self.guesses.push(Guess {
+            day,
+            part,
+            answer,
+            feedback,
+            at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs()),
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use crate::{Answer, Part};
+
+    use super::{Bounds, Conflict, Feedback, History};
+
+    #[test]
+    fn test_history_round_trip() {
+        let data_dir = env::temp_dir().join("aoc-history-test-round-trip");
+        let _ = fs::remove_dir_all(&data_dir);
+        let mut history = History::load(&data_dir, 2023).unwrap();
+        assert_eq!(history.guesses(5, Part::One).count(), 0);
+
+        history.record(5, Part::One, Answer::from(40), Feedback::TooHigh);
+        history.record(5, Part::One, Answer::from(35), Feedback::Correct);
+        history.record(5, Part::Two, Answer::from(46), Feedback::Incorrect);
+        history.save().unwrap();
+
+        let history = History::load(&data_dir, 2023).unwrap();
+        assert_eq!(history.guesses(5, Part::One).count(), 2);
+        assert_eq!(history.correct(5, Part::One), Some(&Answer::from(35)));
+        assert_eq!(history.correct(5, Part::Two), None);
+        assert_eq!(
+            history
+                .find(5, Part::Two, &Answer::from(46))
+                .map(|guess| guess.feedback),
+            Some(Feedback::Incorrect)
+        );
+        assert!(fs::read_to_string(History::path(&data_dir, 2023))
+            .unwrap()
+            .contains("\"feedback\": \"too_high\""));
+        fs::remove_dir_all(data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_bounds_and_check() {
+        let mut history =
+            History::load(&env::temp_dir().join("aoc-history-test-unsaved"), 2023).unwrap();
+        let check = |history: &History, answer: i64| history.check(5, Part::One, &answer.into());
+        assert_eq!(history.bounds(5, Part::One), Bounds::default());
+        assert_eq!(check(&history, 1), None);
+
+        history.record(
+            5,
+            Part::One,
+            Answer::from(4_294_967_000_u64),
+            Feedback::TooHigh,
+        );
+        history.record(5, Part::One, Answer::from(100), Feedback::TooLow);
+        history.record(5, Part::One, Answer::from(90_000), Feedback::TooHigh);
+        history.record(5, Part::One, Answer::from(20), Feedback::TooLow);
+        history.record(5, Part::One, Answer::from(500), Feedback::Incorrect);
+        history.record(5, Part::Two, Answer::from("abc"), Feedback::TooHigh);
+        assert_eq!(
+            history.bounds(5, Part::One),
+            Bounds {
+                lower: Some(100.into()),
+                upper: Some(90_000.into()),
+            }
+        );
+
+        assert_eq!(check(&history, 1_000), None);
+        assert_eq!(
+            check(&history, 500),
+            Some(Conflict::Rejected(Feedback::Incorrect))
+        );
+        assert_eq!(
+            check(&history, 4_294_967_000),
+            Some(Conflict::Rejected(Feedback::TooHigh))
+        );
+        assert_eq!(
+            check(&history, 90_001),
+            Some(Conflict::TooHigh {
+                bound: 90_000.into()
+            })
+        );
+        assert_eq!(
+            check(&history, 50),
+            Some(Conflict::TooLow { bound: 100.into() })
+        );
+        assert_eq!(history.check(5, Part::Two, &"abd".into()), None);
+
+        history.record(5, Part::One, Answer::from(1_000), Feedback::Correct);
+        assert_eq!(check(&history, 1_000), None);
+        assert_eq!(
+            check(&history, 1_001),
+            Some(Conflict::Differs {
+                correct: 1_000.into()
+            })
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    convert::Infallible,
+    env,
+    error::Error,
+    fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator};
+
+pub const DATA_DIR_VAR: &str = "AOC_DATA_DIR";
+const DEFAULT_DATA_DIR: &str = "data";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Source {
+    /// `<data dir>/aoc_<year>/day_<day>.txt`, see [`data_dir`]. An empty file is a placeholder
+    /// and counts as missing.
+    #[default]
+    DataDir,
+    Path(PathBuf),
+    Stdin,
+}
+
+impl FromStr for Source {
+    type Err = Infallible;
+
+    /// `-` selects stdin, anything else is a file path.
+    fn from_str(arg: &str) -> Result<Self, Self::Err> {
+        Ok(if arg == "-" {
+            Source::Stdin
+        } else {
+            Source::Path(arg.into())
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing {
+        year: u16,
+        day: u8,
+        path: PathBuf,
+    },
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    Invalid {
+        path: PathBuf,
+        reason: String,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Missing { year, day, path } => {
+                write!(f, "input for {year}.{day} not found at {}", path.display())
+            }
+            InputError::Io {
+                path: Some(path),
+                source,
+            } => write!(f, "cannot read {}: {source}", path.display()),
+            InputError::Io { path: None, source } => write!(f, "cannot read stdin: {source}"),
+            InputError::Invalid { path, reason } => write!(f, "{}: {reason}", path.display()),
+        }
+    }
+}
+
+impl Error for InputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            InputError::Missing { .. } | InputError::Invalid { .. } => None,
+            InputError::Io { source, .. } => Some(source),
+        }
+    }
+}
+
+/// The data directory: `$AOC_DATA_DIR` if set, `./data` otherwise.
+pub fn data_dir() -> PathBuf {
+    env::var_os(DATA_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_DIR))
+}
+
+pub fn year_dir(data_dir: &Path, year: u16) -> PathBuf {
+    data_dir.join(format!("aoc_{year}"))
+}
+
+pub fn input_path(data_dir: &Path, year: u16, day: u8) -> PathBuf {
+    year_dir(data_dir, year).join(format!("day_{day}.txt"))
+}
+
+pub fn load(year: u16, day: u8, source: &Source) -> Result<String, InputError> {
+    match source {
+        Source::DataDir => {
+            let path = input_path(&data_dir(), year, day);
+            match load_file(year, day, &path)? {
+                data if data.is_empty() => Err(InputError::Missing { year, day, path }),
+                data => Ok(data),
+            }
+        }
+        Source::Path(path) => load_file(year, day, path),
+        Source::Stdin => {
+            let mut data = String::new();
+            io::stdin()
+                .read_to_string(&mut data)
+                .map_err(|source| InputError::Io { path: None, source })?;
+            Ok(data)
+        }
+    }
+}
+
+fn load_file(year: u16, day: u8, path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|source| match source.kind() {
+        io::ErrorKind::NotFound => InputError::Missing {
+            year,
+            day,
+            path: path.to_owned(),
+        },
+        _ => InputError::Io {
+            path: Some(path.to_owned()),
+            source,
+        },
+    })
+}
+
+/// Lines paired with their 0-based index, for parsers that report error locations.
+pub fn numbered_lines(data: &str) -> impl IndexedParallelIterator<Item = (usize, &str)> {
+    data.lines().collect::<Vec<_>>().into_par_iter().enumerate()
+}
+
+/// Loads the real input for a test, or returns `None` (and says so) when it is absent.
+#[cfg(test)]
+pub(crate) fn load_or_skip(year: u16, day: u8) -> Option<String> {
+    match load(year, day, &Source::DataDir) {
+        Ok(data) => Some(data),
+        Err(err @ InputError::Missing { .. }) => {
+            eprintln!("skipping: {err}");
+            None
+        }
+        Err(err) => panic!("{err}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::Path};
+
+    use super::{input_path, load, InputError, Source};
+
+    #[test]
+    fn test_input_path() {
+        assert_eq!(
+            input_path(Path::new("data"), 2023, 5),
+            Path::new("data/aoc_2023/day_5.txt")
+        );
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let path = env::temp_dir().join("aoc-input-test-missing.txt");
+        match load(2023, 7, &Source::Path(path.clone())) {
+            Err(InputError::Missing {
+                year: 2023,
+                day: 7,
+                path: missing,
+            }) => assert_eq!(missing, path),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_explicit_path() {
+        let path = env::temp_dir().join("aoc-input-test-explicit.txt");
+        fs::write(&path, "Time: 7\nDistance: 9\n").unwrap();
+        let data = load(2023, 6, &Source::Path(path.clone())).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(data, "Time: 7\nDistance: 9\n");
+    }
+
+    #[test]
+    fn test_source_from_arg() {
+        assert_eq!("-".parse(), Ok(Source::Stdin));
+        assert_eq!("day_1.txt".parse(), Ok(Source::Path("day_1.txt".into())));
+    }
+}
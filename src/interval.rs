@@ -0,0 +1,367 @@
+use std::{fmt, ops::Range};
+
+/// The half-open range `start..end`. Any interval with `start >= end` is empty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub const fn new(start: i64, end: i64) -> Self {
+        Self { start, end }
+    }
+
+    pub const fn with_len(start: i64, len: i64) -> Self {
+        Self::new(start, start + len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn len(&self) -> u64 {
+        if self.is_empty() {
+            0
+        } else {
+            self.end.abs_diff(self.start)
+        }
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The common part, which may be empty.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// The parts before and after `other`, either of which may be empty.
+    pub fn difference(&self, other: &Self) -> (Self, Self) {
+        if other.is_empty() || other.start >= self.end {
+            return (*self, Self::new(self.end, self.end));
+        }
+        if other.end <= self.start {
+            return (Self::new(self.start, self.start), *self);
+        }
+        (
+            Self::new(self.start, other.start),
+            Self::new(other.end, self.end),
+        )
+    }
+
+    /// The parts below and from `at`, either of which may be empty.
+    pub fn split_at(&self, at: i64) -> (Self, Self) {
+        let at = at.clamp(self.start, self.end.max(self.start));
+        (Self::new(self.start, at), Self::new(at, self.end))
+    }
+
+    pub fn shift(&self, offset: i64) -> Self {
+        Self::new(self.start + offset, self.end + offset)
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+impl From<Range<i64>> for Interval {
+    fn from(range: Range<i64>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+/// A set of integers stored as sorted, disjoint, non-adjacent, non-empty intervals.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Interval> + '_ {
+        self.intervals.iter().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// How many integers are in the set.
+    pub fn len(&self) -> u64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.intervals.last().map(|interval| interval.end - 1)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let idx = self
+            .intervals
+            .partition_point(|interval| interval.end <= value);
+        self.intervals
+            .get(idx)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: impl Into<Interval>) {
+        let interval = interval.into();
+        if interval.is_empty() {
+            return;
+        }
+        // Everything from `first` to `last` overlaps or touches `interval`, and merges into it.
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, i| {
+                Interval::new(merged.start.min(i.start), merged.end.max(i.end))
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut left, mut right) = (self.intervals.iter(), other.intervals.iter());
+        let (mut a, mut b) = (left.next(), right.next());
+        while let (Some(x), Some(y)) = (a, b) {
+            let common = x.intersection(y);
+            if !common.is_empty() {
+                intervals.push(common);
+            }
+            if x.end < y.end {
+                a = left.next();
+            } else {
+                b = right.next();
+            }
+        }
+        // Pieces of disjoint, non-adjacent intervals are themselves disjoint and non-adjacent.
+        Self { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let mut cuts = other.intervals.iter().peekable();
+        for &interval in &self.intervals {
+            let mut rest = interval;
+            while let Some(cut) = cuts.peek() {
+                if cut.end <= rest.start {
+                    cuts.next();
+                    continue;
+                }
+                if cut.start >= rest.end {
+                    break;
+                }
+                let (before, after) = rest.difference(cut);
+                if !before.is_empty() {
+                    intervals.push(before);
+                }
+                rest = after;
+                if cut.end > interval.end {
+                    break;
+                }
+                cuts.next();
+            }
+            if !rest.is_empty() {
+                intervals.push(rest);
+            }
+        }
+        Self { intervals }
+    }
+
+    /// The intervals cut at every breakpoint, so no piece has a breakpoint strictly inside it.
+    /// Pieces are sorted and cover exactly the set.
+    pub fn split(&self, breakpoints: impl IntoIterator<Item = i64>) -> Vec<Interval> {
+        let mut breakpoints = breakpoints.into_iter().collect::<Vec<_>>();
+        breakpoints.sort_unstable();
+        breakpoints.dedup();
+        let mut pieces = Vec::new();
+        for &interval in &self.intervals {
+            let first = breakpoints.partition_point(|&at| at <= interval.start);
+            let mut rest = interval;
+            for &at in breakpoints[first..]
+                .iter()
+                .take_while(|&&at| at < interval.end)
+            {
+                let (piece, after) = rest.split_at(at);
+                pieces.push(piece);
+                rest = after;
+            }
+            pieces.push(rest);
+        }
+        pieces
+    }
+}
+
+impl<I: Into<Interval>> FromIterator<I> for IntervalSet {
+    /// Sorts and coalesces `iter`, dropping empty intervals.
+    fn from_iter<T: IntoIterator<Item = I>>(iter: T) -> Self {
+        let mut sorted = iter
+            .into_iter()
+            .map(Into::into)
+            .filter(|interval: &Interval| !interval.is_empty())
+            .collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let mut intervals: Vec<Interval> = Vec::with_capacity(sorted.len());
+        for interval in sorted {
+            match intervals.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => intervals.push(interval),
+            }
+        }
+        Self { intervals }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::{collection::vec, prelude::*};
+
+    use super::{Interval, IntervalSet};
+
+    #[test]
+    fn test_interval() {
+        let interval = Interval::with_len(10, 5);
+        assert_eq!(interval, Interval::from(10..15));
+        assert_eq!(interval.len(), 5);
+        assert_eq!(Interval::new(3, 1).len(), 0);
+        assert_eq!(
+            interval.intersection(&(12..20).into()),
+            Interval::new(12, 15)
+        );
+        assert!(!interval.overlaps(&(15..20).into()));
+        assert_eq!(
+            interval.difference(&(11..13).into()),
+            (Interval::new(10, 11), Interval::new(13, 15))
+        );
+        assert_eq!(
+            interval.difference(&(0..5).into()),
+            (Interval::new(10, 10), interval)
+        );
+        assert_eq!(
+            interval.difference(&(15..20).into()),
+            (interval, Interval::new(15, 15))
+        );
+        assert_eq!(
+            interval.split_at(20),
+            (Interval::new(10, 15), Interval::new(15, 15))
+        );
+        assert_eq!(interval.shift(-10), Interval::new(0, 5));
+    }
+
+    #[test]
+    fn test_set() {
+        let mut set = [5..8, 0..2, 2..3, 10..10]
+            .into_iter()
+            .collect::<IntervalSet>();
+        assert_eq!(set.intervals(), [Interval::new(0, 3), Interval::new(5, 8)]);
+        set.insert(3..5);
+        assert_eq!(set.intervals(), [Interval::new(0, 8)]);
+        set.insert(20..30);
+        assert_eq!((set.len(), set.min(), set.max()), (18, Some(0), Some(29)));
+        assert!(set.contains(7) && !set.contains(8) && set.contains(20));
+
+        let other = [Interval::new(6, 22)].into_iter().collect::<IntervalSet>();
+        assert_eq!(
+            set.difference(&other).intervals(),
+            [Interval::new(0, 6), Interval::new(22, 30)]
+        );
+        assert_eq!(
+            set.intersection(&other).intervals(),
+            [Interval::new(6, 8), Interval::new(20, 22)]
+        );
+        assert_eq!(
+            set.split([4, 0, 25, 100]),
+            [
+                Interval::new(0, 4),
+                Interval::new(4, 8),
+                Interval::new(20, 25),
+                Interval::new(25, 30)
+            ]
+        );
+    }
+
+    fn set() -> impl Strategy<Value = IntervalSet> {
+        vec((-50i64..50, 0i64..20), 0..8).prop_map(|intervals| {
+            intervals
+                .into_iter()
+                .map(|(start, len)| Interval::with_len(start, len))
+                .collect()
+        })
+    }
+
+    fn members(set: &IntervalSet) -> Vec<i64> {
+        (-100..100).filter(|&value| set.contains(value)).collect()
+    }
+
+    fn normalised(set: &IntervalSet) -> bool {
+        set.intervals().iter().all(|interval| !interval.is_empty())
+            && set
+                .intervals()
+                .windows(2)
+                .all(|pair| pair[0].end < pair[1].start)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_set_operations(a in set(), b in set()) {
+            let union = a.union(&b);
+            let intersection = a.intersection(&b);
+            let difference = a.difference(&b);
+            for result in [&union, &intersection, &difference] {
+                prop_assert!(normalised(result), "{result:?}");
+            }
+            for value in -100..100 {
+                let (in_a, in_b) = (a.contains(value), b.contains(value));
+                prop_assert_eq!(union.contains(value), in_a || in_b);
+                prop_assert_eq!(intersection.contains(value), in_a && in_b);
+                prop_assert_eq!(difference.contains(value), in_a && !in_b);
+            }
+            prop_assert_eq!(a.len(), members(&a).len() as u64);
+        }
+
+        #[test]
+        fn prop_insert_matches_union(a in set(), start in -50i64..50, len in 0i64..20) {
+            let mut inserted = a.clone();
+            inserted.insert(Interval::with_len(start, len));
+            let single = [Interval::with_len(start, len)].into_iter().collect();
+            prop_assert_eq!(inserted, a.union(&single));
+        }
+
+        #[test]
+        fn prop_split(a in set(), breakpoints in vec(-60i64..60, 0..6)) {
+            let pieces = a.split(breakpoints.iter().copied());
+            prop_assert_eq!(pieces.iter().copied().collect::<IntervalSet>(), a);
+            prop_assert!(pieces.windows(2).all(|pair| pair[0].end <= pair[1].start));
+            for piece in pieces {
+                prop_assert!(!piece.is_empty());
+                prop_assert!(breakpoints
+                    .iter()
+                    .all(|&at| at <= piece.start || at >= piece.end));
+            }
+        }
+    }
+}
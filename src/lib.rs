@@ -1 +1,25 @@
-mod aoc_2023;
+mod answer;
+pub mod aoc_2023;
+pub mod bench;
+pub mod client;
+mod error;
+pub mod exec;
+pub mod fetch;
+pub mod geometry;
+pub mod grammar;
+pub mod grid;
+pub mod history;
+pub mod input;
+pub mod interval;
+pub mod math;
+pub mod parse;
+pub mod range_map;
+pub mod scaffold;
+pub mod search;
+mod solution;
+pub mod submit;
+pub mod verify;
+
+pub use answer::Answer;
+pub use error::{AocError, ParseError, PuzzleId};
+pub use solution::{find, puzzles, solve, solve_in, Part, Puzzle, Solution, Timed};
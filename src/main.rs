@@ -0,0 +1,408 @@
+use std::{
+    env,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use aoc::{
+    bench,
+    client::Client,
+    exec::{self, Differential, Mode},
+    fetch, find,
+    history::History,
+    input::{self, Source},
+    puzzles, scaffold,
+    submit::{self, Response},
+    verify, Answer, Part, Puzzle,
+};
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Run Advent of Code solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run one puzzle, or every registered puzzle with --all
+    Run {
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        year: Option<u16>,
+        #[arg(required_unless_present = "all")]
+        day: Option<u8>,
+        /// Run only this part (both parts by default)
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Read the puzzle input from this file (`-` for stdin) instead of the data directory
+        #[arg(short, long, conflicts_with = "all")]
+        input: Option<Source>,
+        #[arg(long)]
+        all: bool,
+        /// `parallel`, `sequential` or a thread count
+        #[arg(short, long, default_value_t = Mode::Parallel)]
+        mode: Mode,
+        /// Run on a dedicated pool with this many threads
+        #[arg(short = 'j', long, conflicts_with = "mode")]
+        threads: Option<NonZeroUsize>,
+    },
+    /// List every registered puzzle
+    List,
+    /// Check every registered puzzle against the answers files
+    Verify,
+    /// Time parsing and solving of registered puzzles with inputs in the data directory
+    Bench {
+        year: Option<u16>,
+        #[arg(requires = "year")]
+        day: Option<u8>,
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Repeat each part this many times
+        #[arg(short, long, default_value_t = 10)]
+        runs: u32,
+        /// Modes to measure, comma separated (`parallel`, `sequential` or thread counts)
+        #[arg(short, long, value_delimiter = ',', default_value = "parallel")]
+        mode: Vec<Mode>,
+        /// Measure every thread count from 1 to this instead of --mode
+        #[arg(long, conflicts_with = "mode")]
+        sweep: Option<NonZeroUsize>,
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Download puzzle inputs into the data directory, skipping cached ones
+    Fetch {
+        year: u16,
+        /// Every registered day of the year by default
+        day: Option<u8>,
+        /// Download again even if the input is cached
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Submit an answer, by default the solver's answer for the data directory input
+    Submit {
+        year: u16,
+        day: u8,
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Submit this answer instead of solving
+        #[arg(short, long, conflicts_with = "input")]
+        answer: Option<Answer>,
+        /// Solve this input (`-` for stdin) instead of the data directory one
+        #[arg(short, long)]
+        input: Option<Source>,
+    },
+    /// Generate and register a new day module with test stubs and an empty input
+    New {
+        year: u16,
+        day: u8,
+        /// The crate to add the day to, instead of the nearest one above the current directory
+        #[arg(long)]
+        root: Option<PathBuf>,
+    },
+    /// Solve registered puzzles sequentially and in parallel and compare the answers
+    Check {
+        year: Option<u16>,
+        #[arg(requires = "year")]
+        day: Option<u8>,
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Parallel runs per part
+        #[arg(short, long, default_value_t = 5)]
+        rounds: u32,
+    },
+}
+
+fn main() -> ExitCode {
+    match Cli::parse().command {
+        Command::Run {
+            year,
+            day,
+            part,
+            input,
+            all,
+            mode,
+            threads,
+        } => {
+            let parts = parts(part);
+            let mode = threads.map_or(mode, Mode::Threads);
+            if all {
+                let failures = puzzles()
+                    .filter(|puzzle| !run(puzzle, &parts, &Source::DataDir, mode))
+                    .count();
+                return exit_code(failures == 0);
+            }
+            let (year, day) = (year.unwrap(), day.unwrap());
+            match find(year, day) {
+                Some(puzzle) => exit_code(run(puzzle, &parts, &input.unwrap_or_default(), mode)),
+                None => {
+                    eprintln!("{year}.{day}: no solution registered");
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Command::List => {
+            for puzzle in puzzles() {
+                println!("{}.{}", puzzle.year, puzzle.day);
+            }
+            ExitCode::SUCCESS
+        }
+        Command::Verify => match verify::verify_all() {
+            Ok(checks) => {
+                for check in &checks {
+                    println!("{check}");
+                }
+                exit_code(!checks.iter().any(|check| check.outcome.is_failure()))
+            }
+            Err(err) => {
+                eprintln!("{err}");
+                ExitCode::FAILURE
+            }
+        },
+        Command::Bench {
+            year,
+            day,
+            part,
+            runs,
+            mode,
+            sweep,
+            json,
+        } => {
+            let parts = parts(part);
+            let modes = match sweep {
+                Some(threads) => (1..=threads.get())
+                    .filter_map(NonZeroUsize::new)
+                    .map(Mode::Threads)
+                    .collect(),
+                None => mode,
+            };
+            let mut success = true;
+            let mut measurements = Vec::new();
+            for (puzzle, data) in with_inputs(year, day) {
+                for part in &parts {
+                    for mode in &modes {
+                        match bench::bench(puzzle, &data, *part, *mode, runs) {
+                            Ok(measurement) => measurements.push(measurement),
+                            Err(err) => {
+                                eprintln!("{}.{}.{part}: {err}", puzzle.year, puzzle.day);
+                                success = false;
+                            }
+                        }
+                    }
+                }
+            }
+            if json {
+                println!("{}", bench::json(&measurements));
+            } else {
+                print!("{}", bench::table(&measurements));
+            }
+            exit_code(success)
+        }
+        Command::Fetch { year, day, refresh } => {
+            let client = match Client::from_env() {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("{err}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            let days = match day {
+                Some(day) => vec![day],
+                None => puzzles()
+                    .filter(|puzzle| puzzle.year == year)
+                    .map(|puzzle| puzzle.day)
+                    .collect(),
+            };
+            let data_dir = input::data_dir();
+            let mut success = true;
+            for day in days {
+                match fetch::fetch(&client, &data_dir, year, day, refresh) {
+                    Ok(fetched) if fetched.downloaded => {
+                        println!("{year}.{day}: saved {}", fetched.path.display())
+                    }
+                    Ok(fetched) => println!("{year}.{day}: cached {}", fetched.path.display()),
+                    Err(err) => {
+                        eprintln!("{year}.{day}: {err}");
+                        success = false;
+                    }
+                }
+            }
+            exit_code(success)
+        }
+        Command::Submit {
+            year,
+            day,
+            part,
+            answer,
+            input,
+        } => {
+            let part = Part::try_from(part).expect("clap checks the part");
+            let label = format!("{year}.{day}.{part}");
+            let answer = match answer {
+                Some(answer) => answer,
+                None => match solve(year, day, part, &input.unwrap_or_default()) {
+                    Ok(answer) => answer,
+                    Err(err) => {
+                        eprintln!("{label}: {err}");
+                        return ExitCode::FAILURE;
+                    }
+                },
+            };
+            let submitted = Client::from_env()
+                .map_err(submit::SubmitError::from)
+                .and_then(|client| {
+                    let mut history = History::load(&input::data_dir(), year)?;
+                    submit::submit(&client, &mut history, day, part, &answer)
+                });
+            match submitted {
+                Ok(response) => {
+                    println!("{label}: {answer} {response}");
+                    exit_code(response == Response::Correct)
+                }
+                Err(err) => {
+                    eprintln!("{label}: {err}");
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Command::New { year, day, root } => {
+            let root = root.or_else(|| {
+                let cwd = env::current_dir().ok()?;
+                scaffold::crate_root(&cwd).map(Path::to_owned)
+            });
+            let Some(root) = root else {
+                eprintln!("{year}.{day}: no Cargo.toml here or above, pass --root");
+                return ExitCode::FAILURE;
+            };
+            // A relative data directory is taken from the crate root, like the sources.
+            let data_dir = root.join(input::data_dir());
+            match scaffold::new_day(&root, &data_dir, year, day) {
+                Ok(touched) => {
+                    for path in touched {
+                        println!("{}", path.display());
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(err) => {
+                    eprintln!("{year}.{day}: {err}");
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Command::Check {
+            year,
+            day,
+            part,
+            rounds,
+        } => {
+            let parts = parts(part);
+            let mut success = true;
+            for (puzzle, data) in with_inputs(year, day) {
+                for part in &parts {
+                    let label = format!("{}.{}.{part}", puzzle.year, puzzle.day);
+                    match exec::differential(puzzle, &data, *part, rounds) {
+                        Ok(Differential::Agree(answer)) => println!("{label}: ok {answer}"),
+                        Ok(Differential::Disagree {
+                            sequential,
+                            parallel,
+                        }) => {
+                            println!(
+                                "{label}: MISMATCH sequential {sequential}, parallel {}",
+                                parallel
+                                    .iter()
+                                    .map(ToString::to_string)
+                                    .collect::<Vec<_>>()
+                                    .join(" / ")
+                            );
+                            success = false;
+                        }
+                        Err(err) => {
+                            eprintln!("{label}: {err}");
+                            success = false;
+                        }
+                    }
+                }
+            }
+            exit_code(success)
+        }
+    }
+}
+
+fn parts(part: Option<u8>) -> Vec<Part> {
+    match part.map(Part::try_from) {
+        Some(Ok(part)) => vec![part],
+        _ => Part::ALL.to_vec(),
+    }
+}
+
+fn exit_code(success: bool) -> ExitCode {
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Registered puzzles matching the filters, paired with their data directory inputs.
+/// Puzzles without an input are reported and skipped.
+fn with_inputs(
+    year: Option<u16>,
+    day: Option<u8>,
+) -> impl Iterator<Item = (&'static Puzzle, String)> {
+    puzzles()
+        .filter(move |puzzle| {
+            year.is_none_or(|year| year == puzzle.year) && day.is_none_or(|day| day == puzzle.day)
+        })
+        .filter_map(
+            |puzzle| match input::load(puzzle.year, puzzle.day, &Source::DataDir) {
+                Ok(data) => Some((puzzle, data)),
+                Err(err) => {
+                    eprintln!("{}.{}: {err}", puzzle.year, puzzle.day);
+                    None
+                }
+            },
+        )
+}
+
+fn solve(year: u16, day: u8, part: Part, source: &Source) -> Result<Answer, aoc::AocError> {
+    let data = input::load(year, day, source)?;
+    aoc::solve(year, day, part, &data)
+}
+
+fn run(puzzle: &Puzzle, parts: &[Part], source: &Source, mode: Mode) -> bool {
+    let data = match input::load(puzzle.year, puzzle.day, source) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("{}.{}: {err}", puzzle.year, puzzle.day);
+            return false;
+        }
+    };
+    // Answers are checked against earlier submissions, but a broken history never stops a run.
+    let history = History::load(&input::data_dir(), puzzle.year)
+        .inspect_err(|err| eprintln!("warning: {err}"))
+        .ok();
+    let mut success = true;
+    for part in parts {
+        match mode.install(|| puzzle.run(&data, *part)) {
+            Ok(solution) => {
+                println!("{}.{}.{part}: {solution}", puzzle.year, puzzle.day);
+                if let Some(conflict) = history
+                    .as_ref()
+                    .and_then(|history| history.check(puzzle.day, *part, &solution))
+                {
+                    eprintln!(
+                        "{}.{}.{part}: warning: {solution} {conflict}",
+                        puzzle.year, puzzle.day
+                    );
+                }
+            }
+            Err(err) => {
+                eprintln!("{}.{}.{part}: {err}", puzzle.year, puzzle.day);
+                success = false;
+            }
+        }
+    }
+    success
+}
@@ -0,0 +1,364 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug},
+    ops::{Add, Div, Mul, Rem, Sub},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    /// An intermediate or final result does not fit the integer type.
+    Overflow,
+    /// A modulus was zero or negative.
+    Modulus,
+    /// A square root of a negative number.
+    Negative,
+    /// The number shares a factor with the modulus, so has no inverse.
+    NotInvertible,
+    /// The congruences contradict each other.
+    NoSolution,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MathError::Overflow => write!(f, "arithmetic overflow"),
+            MathError::Modulus => write!(f, "modulus must be positive"),
+            MathError::Negative => write!(f, "square root of a negative number"),
+            MathError::NotInvertible => write!(f, "no modular inverse"),
+            MathError::NoSolution => write!(f, "congruences have no common solution"),
+        }
+    }
+}
+
+impl Error for MathError {}
+
+/// The primitive integers, with the checked operations the helpers here need.
+pub trait Integer:
+    Copy
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_div(self, other: Self) -> Option<Self>;
+    fn checked_rem(self, other: Self) -> Option<Self>;
+    fn checked_neg(self) -> Option<Self>;
+    fn rem_euclid(self, modulus: Self) -> Self;
+    /// `self * other` modulo a positive `modulus`, multiplied in 128 bits so it only fails
+    /// when that overflows.
+    fn mul_mod(self, other: Self, modulus: Self) -> Option<Self>;
+}
+
+/// Integers that can be negative, which extended Euclid and the CRT need.
+pub trait Signed: Integer {}
+
+macro_rules! integer {
+    ($($int:ty => $wide:ty),*) => {
+        $(impl Integer for $int {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const TWO: Self = 2;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$int>::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$int>::checked_sub(self, other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$int>::checked_mul(self, other)
+            }
+
+            fn checked_div(self, other: Self) -> Option<Self> {
+                <$int>::checked_div(self, other)
+            }
+
+            fn checked_rem(self, other: Self) -> Option<Self> {
+                <$int>::checked_rem(self, other)
+            }
+
+            fn checked_neg(self) -> Option<Self> {
+                <$int>::checked_neg(self)
+            }
+
+            fn rem_euclid(self, modulus: Self) -> Self {
+                <$int>::rem_euclid(self, modulus)
+            }
+
+            fn mul_mod(self, other: Self, modulus: Self) -> Option<Self> {
+                let product = (self as $wide).checked_mul(other as $wide)?;
+                Some(product.rem_euclid(modulus as $wide) as $int)
+            }
+        })*
+    };
+}
+
+integer!(
+    u8 => u128, u16 => u128, u32 => u128, u64 => u128, u128 => u128, usize => u128,
+    i8 => i128, i16 => i128, i32 => i128, i64 => i128, i128 => i128, isize => i128
+);
+
+impl Signed for i8 {}
+impl Signed for i16 {}
+impl Signed for i32 {}
+impl Signed for i64 {}
+impl Signed for i128 {}
+impl Signed for isize {}
+
+fn checked<T>(value: Option<T>) -> Result<T, MathError> {
+    value.ok_or(MathError::Overflow)
+}
+
+fn abs<T: Integer>(value: T) -> Result<T, MathError> {
+    if value < T::ZERO {
+        checked(value.checked_neg())
+    } else {
+        Ok(value)
+    }
+}
+
+/// The greatest common divisor of the magnitudes; `gcd(0, 0)` is 0.
+pub fn gcd<T: Integer>(a: T, b: T) -> Result<T, MathError> {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        // Only `MIN % -1` overflows, and anything divided by -1 leaves nothing.
+        (a, b) = (b, a.checked_rem(b).unwrap_or(T::ZERO));
+    }
+    abs(a)
+}
+
+pub fn gcd_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Result<T, MathError> {
+    values
+        .into_iter()
+        .try_fold(T::ZERO, |acc, value| gcd(acc, value))
+}
+
+/// The least common multiple of the magnitudes; 0 if either is 0.
+pub fn lcm<T: Integer>(a: T, b: T) -> Result<T, MathError> {
+    if a == T::ZERO || b == T::ZERO {
+        return Ok(T::ZERO);
+    }
+    let (a, b) = (abs(a)?, abs(b)?);
+    checked((a / gcd(a, b)?).checked_mul(b))
+}
+
+/// The least common multiple of all `values`, or 1 if there are none.
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Result<T, MathError> {
+    values
+        .into_iter()
+        .try_fold(T::ONE, |acc, value| lcm(acc, value))
+}
+
+/// `(g, x, y)` with `a * x + b * y == g`, where `g` is the non-negative gcd of `a` and `b`.
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> Result<(T, T, T), MathError> {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+    while r != T::ZERO {
+        let Some(quotient) = old_r.checked_div(r) else {
+            // Only `MIN / -1` overflows; -1 divides `MIN`, so it is the last remainder.
+            (old_r, old_x, old_y) = (r, x, y);
+            break;
+        };
+        (old_r, r) = (r, checked(old_r.checked_rem(r))?);
+        (old_x, x) = (
+            x,
+            checked(old_x.checked_sub(checked(quotient.checked_mul(x))?))?,
+        );
+        (old_y, y) = (
+            y,
+            checked(old_y.checked_sub(checked(quotient.checked_mul(y))?))?,
+        );
+    }
+    if old_r < T::ZERO {
+        Ok((
+            checked(old_r.checked_neg())?,
+            checked(old_x.checked_neg())?,
+            checked(old_y.checked_neg())?,
+        ))
+    } else {
+        Ok((old_r, old_x, old_y))
+    }
+}
+
+fn check_modulus<T: Integer>(modulus: T) -> Result<(), MathError> {
+    if modulus > T::ZERO {
+        Ok(())
+    } else {
+        Err(MathError::Modulus)
+    }
+}
+
+/// `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`.
+pub fn mod_inverse<T: Signed>(a: T, modulus: T) -> Result<T, MathError> {
+    check_modulus(modulus)?;
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus)?;
+    if g != T::ONE {
+        return Err(MathError::NotInvertible);
+    }
+    Ok(x.rem_euclid(modulus))
+}
+
+/// `base` to the power `exp`, modulo `modulus`, in `0..modulus`. Fails only for 128-bit
+/// moduli whose square does not fit.
+pub fn mod_pow<T: Integer>(base: T, mut exp: u64, modulus: T) -> Result<T, MathError> {
+    check_modulus(modulus)?;
+    let mut base = base.rem_euclid(modulus);
+    let mut result = T::ONE.rem_euclid(modulus);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = checked(result.mul_mod(base, modulus))?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = checked(base.mul_mod(base, modulus))?;
+        }
+    }
+    Ok(result)
+}
+
+/// The smallest non-negative `x` satisfying every `x ≡ residue (mod modulus)`, with the lcm
+/// of the moduli, which every other solution differs by. Moduli need not be coprime.
+pub fn crt<T: Signed>(congruences: impl IntoIterator<Item = (T, T)>) -> Result<(T, T), MathError> {
+    congruences
+        .into_iter()
+        .try_fold((T::ZERO, T::ONE), |(x, m), (residue, modulus)| {
+            check_modulus(modulus)?;
+            let residue = residue.rem_euclid(modulus);
+            let g = gcd(m, modulus)?;
+            let diff = checked(residue.checked_sub(x))?;
+            if diff % g != T::ZERO {
+                return Err(MathError::NoSolution);
+            }
+            // Solve m * k ≡ diff (mod modulus) for k, then x + m * k satisfies both.
+            let step = modulus / g;
+            let k = if step == T::ONE {
+                T::ZERO
+            } else {
+                let inverse = mod_inverse((m / g).rem_euclid(step), step)?;
+                checked((diff / g).rem_euclid(step).mul_mod(inverse, step))?
+            };
+            let lcm = checked(m.checked_mul(step))?;
+            let x = checked(x.checked_add(checked(m.checked_mul(k))?))?.rem_euclid(lcm);
+            Ok((x, lcm))
+        })
+}
+
+/// The largest `r` with `r * r <= n`.
+pub fn isqrt<T: Integer>(n: T) -> Result<T, MathError> {
+    if n < T::ZERO {
+        return Err(MathError::Negative);
+    }
+    if n < T::TWO {
+        return Ok(n);
+    }
+    // Newton's method from above, halving each term separately so nothing overflows.
+    let average = |a: T, b: T| a / T::TWO + b / T::TWO + (a % T::TWO + b % T::TWO) / T::TWO;
+    let mut root = n;
+    let mut next = average(root, n / root);
+    while next < root {
+        root = next;
+        next = average(root, n / root);
+    }
+    Ok(root)
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::{
+        crt, extended_gcd, gcd, gcd_all, isqrt, lcm, lcm_all, mod_inverse, mod_pow, MathError,
+    };
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12u8, 18), Ok(6));
+        assert_eq!(gcd(-12i32, 18), Ok(6));
+        assert_eq!(gcd(0u64, 0), Ok(0));
+        assert_eq!(gcd(i8::MIN, 0), Err(MathError::Overflow));
+        assert_eq!(gcd(i64::MIN, -1), Ok(1));
+        assert_eq!(lcm(4i16, -6), Ok(12));
+        assert_eq!(lcm(200u8, 3), Err(MathError::Overflow));
+        assert_eq!(gcd_all([84u32, 126, 210]), Ok(42));
+        assert_eq!(lcm_all([2u64, 3, 4, 5]), Ok(60));
+        assert_eq!(lcm_all::<u64>([]), Ok(1));
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(extended_gcd(240i64, 46), Ok((2, -9, 47)));
+        assert_eq!(extended_gcd(i64::MIN, -1), Ok((1, 0, -1)));
+        assert_eq!(extended_gcd(-1i64, i64::MIN), Ok((1, -1, 0)));
+        assert_eq!(mod_inverse(3i32, 11), Ok(4));
+        assert_eq!(mod_inverse(-3i32, 11), Ok(7));
+        assert_eq!(mod_inverse(6i32, 9), Err(MathError::NotInvertible));
+        assert_eq!(mod_inverse(6i32, 0), Err(MathError::Modulus));
+        assert_eq!(mod_pow(4u32, 13, 497), Ok(445));
+        assert_eq!(mod_pow(-2i64, 3, 5), Ok(2));
+        assert_eq!(mod_pow(7u8, 0, 1), Ok(0));
+        assert_eq!(mod_pow(100u8, 2, 251), Ok(211));
+        // Fermat's little theorem, with a prime modulus just above 2^32.
+        assert_eq!(mod_pow(3u64, 4294967310, 4294967311), Ok(1));
+        assert_eq!(mod_pow(2u64, 64, u64::MAX), Ok(1));
+        assert_eq!(
+            mod_pow(u128::MAX - 1, 2, u128::MAX),
+            Err(MathError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2i64, 3), (3, 5), (2, 7)]), Ok((23, 105)));
+        assert_eq!(crt([(2i64, 4), (4, 6)]), Ok((10, 12)));
+        assert_eq!(crt([(1i64, 4), (2, 6)]), Err(MathError::NoSolution));
+        assert_eq!(crt([(-1i64, 5)]), Ok((4, 5)));
+        assert_eq!(crt::<i64>([]), Ok((0, 1)));
+        assert_eq!(crt([(0i8, 11), (0, 13)]), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(isqrt(0u8), Ok(0));
+        assert_eq!(isqrt(255u8), Ok(15));
+        assert_eq!(isqrt(u64::MAX), Ok(u32::MAX as u64));
+        assert_eq!(isqrt(u128::MAX), Ok(u64::MAX as u128));
+        assert_eq!(isqrt(-1i32), Err(MathError::Negative));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_isqrt(n: u64) {
+            let root = isqrt(n).unwrap() as u128;
+            prop_assert!(root * root <= n as u128 && (root + 1) * (root + 1) > n as u128);
+        }
+
+        #[test]
+        fn prop_crt(a in -1000i64..1000, m in 1i64..500, b in -1000i64..1000, n in 1i64..500) {
+            match crt([(a, m), (b, n)]) {
+                Ok((x, lcm)) => {
+                    prop_assert_eq!(lcm, super::lcm(m, n).unwrap());
+                    prop_assert!((0..lcm).contains(&x));
+                    prop_assert_eq!((x - a).rem_euclid(m), 0);
+                    prop_assert_eq!((x - b).rem_euclid(n), 0);
+                }
+                Err(err) => {
+                    prop_assert_eq!(err, MathError::NoSolution);
+                    prop_assert!((0..m * n).all(|x| (x - a).rem_euclid(m) != 0 || (x - b).rem_euclid(n) != 0));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::{fmt, marker::PhantomData, str::FromStr};
+
+/// Why a piece of input was rejected. `token` is a subslice of the text that was searched, so
+/// it can be located with [`PuzzleId::invalid`](crate::PuzzleId::invalid).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenError<'a> {
+    OutOfRange(&'a str),
+    /// A negative number where only unsigned ones are allowed.
+    Negative(&'a str),
+    NoHeader(&'a str),
+    MissingLabel {
+        line: &'a str,
+        label: &'static str,
+    },
+    Count {
+        text: &'a str,
+        expected: usize,
+        found: usize,
+    },
+    /// A [grammar](crate::grammar) parser did not match at `found`.
+    Expected {
+        found: &'a str,
+        expected: &'static str,
+    },
+    Tag {
+        found: &'a str,
+        tag: &'static str,
+    },
+}
+
+impl<'a> TokenError<'a> {
+    pub fn token(&self) -> &'a str {
+        match *self {
+            TokenError::OutOfRange(token) | TokenError::Negative(token) => token,
+            TokenError::NoHeader(line) | TokenError::MissingLabel { line, .. } => line,
+            TokenError::Count { text, .. } => text,
+            TokenError::Expected { found, .. } | TokenError::Tag { found, .. } => found,
+        }
+    }
+
+    /// Whether a grammar parser failed without matching anything, as opposed to matching
+    /// something invalid.
+    pub fn is_mismatch(&self) -> bool {
+        matches!(self, TokenError::Expected { .. } | TokenError::Tag { .. })
+    }
+}
+
+impl fmt::Display for TokenError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenError::OutOfRange(_) => write!(f, "number out of range"),
+            TokenError::Negative(_) => write!(f, "expected a non-negative number"),
+            TokenError::NoHeader(_) => write!(f, "expected `<label>: <values>`"),
+            TokenError::MissingLabel { label, .. } => write!(f, "expected `{label}: <values>`"),
+            TokenError::Count {
+                expected, found, ..
+            } => write!(f, "expected {expected} numbers, found {found}"),
+            TokenError::Expected { expected, .. } => write!(f, "expected {expected}"),
+            TokenError::Tag { tag, .. } => write!(f, "expected `{tag}`"),
+        }
+    }
+}
+
+/// The primitive integers. Unsigned types reject negative numbers instead of dropping the sign.
+pub trait Int: FromStr + Copy + Default {
+    const SIGNED: bool;
+}
+
+macro_rules! int {
+    ($signed:literal: $($int:ty),*) => {
+        $(impl Int for $int {
+            const SIGNED: bool = $signed;
+        })*
+    };
+}
+
+int!(false: u8, u16, u32, u64, u128, usize);
+int!(true: i8, i16, i32, i64, i128, isize);
+
+/// Every run of digits in `text`, in order. A `-` directly before a run is its sign unless it
+/// follows a letter or digit, so `1-3` is two numbers and `x=-3` is one.
+pub fn ints<T: Int>(text: &str) -> Ints<'_, T> {
+    Ints {
+        text,
+        pos: 0,
+        int: PhantomData,
+    }
+}
+
+pub struct Ints<'a, T> {
+    text: &'a str,
+    pos: usize,
+    int: PhantomData<T>,
+}
+
+impl<'a, T: Int> Iterator for Ints<'a, T> {
+    type Item = Result<T, TokenError<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.text.as_bytes();
+        let start = self.pos + bytes[self.pos..].iter().position(u8::is_ascii_digit)?;
+        let end = bytes[start..]
+            .iter()
+            .position(|byte| !byte.is_ascii_digit())
+            .map_or(bytes.len(), |len| start + len);
+        self.pos = end;
+        let negative = start > 0
+            && bytes[start - 1] == b'-'
+            && (start == 1 || !bytes[start - 2].is_ascii_alphanumeric());
+        let token = &self.text[start - usize::from(negative)..end];
+        if negative && !T::SIGNED {
+            return Some(Err(TokenError::Negative(token)));
+        }
+        Some(token.parse().map_err(|_| TokenError::OutOfRange(token)))
+    }
+}
+
+/// Exactly `N` integers from `text`.
+pub fn int_array<T: Int, const N: usize>(text: &str) -> Result<[T; N], TokenError<'_>> {
+    let mut values = [T::default(); N];
+    let mut found = 0;
+    for value in ints(text) {
+        if let Some(slot) = values.get_mut(found) {
+            *slot = value?;
+        }
+        found += 1;
+    }
+    if found == N {
+        Ok(values)
+    } else {
+        Err(TokenError::Count {
+            text,
+            expected: N,
+            found,
+        })
+    }
+}
+
+/// Splits `Label: values` at the first colon, trimming both sides.
+pub fn header(line: &str) -> Result<(&str, &str), TokenError<'_>> {
+    line.split_once(':')
+        .map(|(label, values)| (label.trim(), values.trim()))
+        .ok_or(TokenError::NoHeader(line))
+}
+
+/// The values of a `label: values` line.
+pub fn labelled<'a>(line: &'a str, label: &'static str) -> Result<&'a str, TokenError<'a>> {
+    match header(line) {
+        Ok((found, values)) if found == label => Ok(values),
+        _ => Err(TokenError::MissingLabel { line, label }),
+    }
+}
+
+/// A run of non-blank lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// 0-based index of the first line in the whole input.
+    pub start: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Section<'a> {
+    /// Lines paired with their 0-based index in the whole input.
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &'a str)> {
+        let start = self.start;
+        self.text
+            .lines()
+            .enumerate()
+            .map(move |(idx, line)| (start + idx, line))
+    }
+
+    pub fn first_line(&self) -> &'a str {
+        self.text.lines().next().unwrap_or_default()
+    }
+}
+
+/// Splits `data` on blank lines, skipping runs of them.
+pub fn sections(data: &str) -> impl Iterator<Item = Section<'_>> {
+    let mut lines = data.lines().enumerate().peekable();
+    std::iter::from_fn(move || {
+        let (start, first) = lines.find(|(_, line)| !line.trim().is_empty())?;
+        let mut last = first;
+        while let Some((_, line)) = lines.next_if(|(_, line)| !line.trim().is_empty()) {
+            last = line;
+        }
+        let begin = first.as_ptr() as usize - data.as_ptr() as usize;
+        let end = last.as_ptr() as usize - data.as_ptr() as usize + last.len();
+        Some(Section {
+            start,
+            text: &data[begin..end],
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{header, int_array, ints, labelled, sections, Section, TokenError};
+
+    #[test]
+    fn test_ints() {
+        let line = "Card  12: 41 -48 | x=-3, 1-3";
+        assert_eq!(
+            ints::<i64>(line).collect::<Result<Vec<_>, _>>(),
+            Ok(vec![12, 41, -48, -3, 1, 3])
+        );
+        let mut unsigned = ints::<u8>(line);
+        assert_eq!(unsigned.next(), Some(Ok(12)));
+        assert_eq!(unsigned.next(), Some(Ok(41)));
+        assert_eq!(unsigned.next(), Some(Err(TokenError::Negative("-48"))));
+        assert_eq!(
+            ints::<u8>("255 256").collect::<Vec<_>>(),
+            [Ok(255), Err(TokenError::OutOfRange("256"))]
+        );
+        assert_eq!(ints::<u128>("no numbers").next(), None);
+    }
+
+    #[test]
+    fn test_int_array() {
+        assert_eq!(int_array::<u32, 3>("50 98 2"), Ok([50, 98, 2]));
+        let err = int_array::<u32, 3>("50 98").unwrap_err();
+        assert_eq!(err.to_string(), "expected 3 numbers, found 2");
+        assert!(matches!(
+            int_array::<u32, 2>("1 2 3"),
+            Err(TokenError::Count { found: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_headers() {
+        assert_eq!(header("Card 1: 41 48"), Ok(("Card 1", "41 48")));
+        assert_eq!(labelled("Time:      7  15", "Time"), Ok("7  15"));
+        let err = labelled("Distance: 9", "Time").unwrap_err();
+        assert_eq!(err.token(), "Distance: 9");
+        assert_eq!(err.to_string(), "expected `Time: <values>`");
+        assert_eq!(header("Time"), Err(TokenError::NoHeader("Time")));
+    }
+
+    #[test]
+    fn test_sections() {
+        let data = "seeds: 1 2\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\n\nlast\n";
+        let all = sections(data).collect::<Vec<_>>();
+        assert_eq!(
+            all,
+            [
+                Section {
+                    start: 0,
+                    text: "seeds: 1 2"
+                },
+                Section {
+                    start: 2,
+                    text: "seed-to-soil map:\n50 98 2\n52 50 48"
+                },
+                Section {
+                    start: 8,
+                    text: "last"
+                },
+            ]
+        );
+        assert_eq!(all[1].first_line(), "seed-to-soil map:");
+        assert_eq!(
+            all[1].lines().collect::<Vec<_>>(),
+            [(2, "seed-to-soil map:"), (3, "50 98 2"), (4, "52 50 48")]
+        );
+        assert_eq!(sections("\n\n").count(), 0);
+    }
+}
@@ -0,0 +1,328 @@
+use std::{error::Error, fmt};
+
+use crate::interval::{Interval, IntervalSet};
+
+/// Moves every value in `source` by `offset`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Shift {
+    pub source: Interval,
+    pub offset: i64,
+}
+
+impl Shift {
+    pub const fn new(source: Interval, offset: i64) -> Self {
+        Self { source, offset }
+    }
+
+    /// The `<destination> <source> <length>` form puzzle inputs use.
+    pub const fn from_lengths(destination: i64, source: i64, len: i64) -> Self {
+        Self::new(Interval::with_len(source, len), destination - source)
+    }
+
+    pub fn destination(&self) -> Interval {
+        self.source.shift(self.offset)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeMapError {
+    /// Two shifts claim the same values.
+    Overlap { first: Interval, second: Interval },
+    /// Some value is the image of two different values, so there is no inverse.
+    NotInvertible { destination: Interval },
+}
+
+impl fmt::Display for RangeMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeMapError::Overlap { first, second } => {
+                write!(f, "range {second} overlaps range {first}")
+            }
+            RangeMapError::NotInvertible { destination } => {
+                write!(f, "range {destination} is also mapped to from elsewhere")
+            }
+        }
+    }
+}
+
+impl Error for RangeMapError {}
+
+/// A piecewise map from integers to integers: values inside a shift's source move by its
+/// offset, and every other value maps to itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RangeMap {
+    /// Sorted by source and disjoint, with no empty sources or zero offsets.
+    shifts: Vec<Shift>,
+}
+
+impl RangeMap {
+    pub fn new(shifts: impl IntoIterator<Item = Shift>) -> Result<Self, RangeMapError> {
+        let mut shifts = shifts
+            .into_iter()
+            .filter(|shift| !shift.source.is_empty())
+            .collect::<Vec<_>>();
+        shifts.sort_unstable_by_key(|shift| shift.source);
+        if let Some(pair) = shifts
+            .windows(2)
+            .find(|pair| pair[0].source.overlaps(&pair[1].source))
+        {
+            return Err(RangeMapError::Overlap {
+                first: pair[0].source,
+                second: pair[1].source,
+            });
+        }
+        // Identity shifts still claim their values above, but change nothing.
+        shifts.retain(|shift| shift.offset != 0);
+        Ok(Self { shifts })
+    }
+
+    pub fn shifts(&self) -> &[Shift] {
+        &self.shifts
+    }
+
+    /// The offset applied to `value`.
+    pub fn offset(&self, value: i64) -> i64 {
+        let idx = self
+            .shifts
+            .partition_point(|shift| shift.source.end <= value);
+        self.shifts
+            .get(idx)
+            .filter(|shift| shift.source.contains(value))
+            .map_or(0, |shift| shift.offset)
+    }
+
+    pub fn get(&self, value: i64) -> i64 {
+        value + self.offset(value)
+    }
+
+    /// Where the shifts start and stop; the offset is constant between consecutive ones.
+    fn breakpoints(&self) -> impl Iterator<Item = i64> + '_ {
+        self.shifts
+            .iter()
+            .flat_map(|shift| [shift.source.start, shift.source.end])
+    }
+
+    /// `set` cut wherever the offset changes, each piece with the offset it moves by.
+    pub fn pieces(&self, set: &IntervalSet) -> Vec<Shift> {
+        set.split(self.breakpoints())
+            .into_iter()
+            .map(|piece| Shift::new(piece, self.offset(piece.start)))
+            .collect()
+    }
+
+    /// The image of every value in `set`.
+    pub fn apply(&self, set: &IntervalSet) -> IntervalSet {
+        self.pieces(set).iter().map(Shift::destination).collect()
+    }
+
+    /// The map that applies `self` and then `then`.
+    pub fn compose(&self, then: &RangeMap) -> RangeMap {
+        let Some(domain) = self.span(then) else {
+            return RangeMap::default();
+        };
+        let sources = self
+            .shifts
+            .iter()
+            .map(|shift| shift.source)
+            .collect::<IntervalSet>();
+        let identity = IntervalSet::from_iter([domain]).difference(&sources);
+        let pieces = self
+            .shifts
+            .iter()
+            .copied()
+            .chain(identity.iter().map(|gap| Shift::new(gap, 0)));
+
+        let mut shifts = Vec::new();
+        for first in pieces {
+            let image = IntervalSet::from_iter([first.destination()]);
+            for piece in image.split(then.breakpoints()) {
+                let offset = first.offset + then.offset(piece.start);
+                shifts.push(Shift::new(piece.shift(-first.offset), offset));
+            }
+        }
+        // The pieces come from disjoint parts of the domain, so they cannot overlap.
+        let mut composed = RangeMap::new(shifts).expect("composed shifts overlap");
+        composed.coalesce();
+        composed
+    }
+
+    /// An interval covering every shift of both maps, in `self`'s coordinates.
+    fn span(&self, then: &RangeMap) -> Option<Interval> {
+        let bounds = self
+            .breakpoints()
+            .chain(then.breakpoints())
+            .collect::<Vec<_>>();
+        Some(Interval::new(*bounds.iter().min()?, *bounds.iter().max()?))
+    }
+
+    /// Merges touching shifts that move by the same offset.
+    fn coalesce(&mut self) {
+        self.shifts.dedup_by(|next, prev| {
+            let touching = prev.source.end == next.source.start && prev.offset == next.offset;
+            if touching {
+                prev.source.end = next.source.end;
+            }
+            touching
+        });
+    }
+
+    /// The map undoing `self`, which exists when the shifts just permute their own sources.
+    pub fn invert(&self) -> Result<RangeMap, RangeMapError> {
+        let inverse = RangeMap::new(
+            self.shifts
+                .iter()
+                .map(|shift| Shift::new(shift.destination(), -shift.offset)),
+        )?;
+        let sources = self
+            .shifts
+            .iter()
+            .map(|shift| shift.source)
+            .collect::<IntervalSet>();
+        let destinations = inverse
+            .shifts
+            .iter()
+            .map(|shift| shift.source)
+            .collect::<IntervalSet>();
+        // A destination outside the sources is also the image of itself.
+        if let Some(destination) = destinations.difference(&sources).iter().next() {
+            return Err(RangeMapError::NotInvertible { destination });
+        }
+        Ok(inverse)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::{collection::vec, prelude::*};
+
+    use crate::interval::{Interval, IntervalSet};
+
+    use super::{RangeMap, RangeMapError, Shift};
+
+    /// The day 5 example's seed-to-soil and soil-to-fertilizer maps.
+    fn seed_to_soil() -> RangeMap {
+        RangeMap::new([
+            Shift::from_lengths(50, 98, 2),
+            Shift::from_lengths(52, 50, 48),
+        ])
+        .unwrap()
+    }
+
+    fn soil_to_fertilizer() -> RangeMap {
+        RangeMap::new([
+            Shift::from_lengths(0, 15, 37),
+            Shift::from_lengths(37, 52, 2),
+            Shift::from_lengths(39, 0, 15),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_get_and_apply() {
+        let map = seed_to_soil();
+        assert_eq!(
+            [79, 14, 55, 13, 99].map(|seed| map.get(seed)),
+            [81, 14, 57, 13, 51]
+        );
+        let seeds =
+            IntervalSet::from_iter([Interval::with_len(79, 14), Interval::with_len(55, 13)]);
+        assert_eq!(
+            map.apply(&seeds).intervals(),
+            [Interval::new(57, 70), Interval::new(81, 95)]
+        );
+        let seeds = IntervalSet::from_iter([Interval::new(40, 100)]);
+        assert_eq!(map.apply(&seeds).intervals(), [Interval::new(40, 100)]);
+    }
+
+    #[test]
+    fn test_overlap() {
+        let err = RangeMap::new([Shift::from_lengths(0, 10, 5), Shift::from_lengths(0, 14, 5)])
+            .unwrap_err();
+        assert_eq!(
+            err,
+            RangeMapError::Overlap {
+                first: Interval::new(10, 15),
+                second: Interval::new(14, 19)
+            }
+        );
+        assert_eq!(err.to_string(), "range 14..19 overlaps range 10..15");
+        assert_eq!(
+            RangeMap::new([
+                Shift::from_lengths(50, 50, 10),
+                Shift::from_lengths(52, 50, 48)
+            ]),
+            Err(RangeMapError::Overlap {
+                first: Interval::new(50, 60),
+                second: Interval::new(50, 98)
+            })
+        );
+    }
+
+    #[test]
+    fn test_invert() {
+        let map = seed_to_soil();
+        let inverse = map.invert().unwrap();
+        for value in 0..120 {
+            assert_eq!(inverse.get(map.get(value)), value);
+        }
+        let squash = RangeMap::new([Shift::from_lengths(0, 10, 5)]).unwrap();
+        assert_eq!(
+            squash.invert(),
+            Err(RangeMapError::NotInvertible {
+                destination: Interval::new(0, 5)
+            })
+        );
+    }
+
+    fn map() -> impl Strategy<Value = RangeMap> {
+        vec((-50i64..50, 1i64..15, -30i64..30), 0..5).prop_map(|shifts| {
+            // Keep the first of any overlapping shifts.
+            let mut kept: Vec<Shift> = Vec::new();
+            for (start, len, offset) in shifts {
+                let shift = Shift::new(Interval::with_len(start, len), offset);
+                if kept
+                    .iter()
+                    .all(|other| !other.source.overlaps(&shift.source))
+                {
+                    kept.push(shift);
+                }
+            }
+            RangeMap::new(kept).unwrap()
+        })
+    }
+
+    #[test]
+    fn test_compose() {
+        let composed = seed_to_soil().compose(&soil_to_fertilizer());
+        for seed in 0..120 {
+            assert_eq!(
+                composed.get(seed),
+                soil_to_fertilizer().get(seed_to_soil().get(seed))
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_compose(first in map(), then in map()) {
+            let composed = first.compose(&then);
+            for value in -150..150 {
+                prop_assert_eq!(composed.get(value), then.get(first.get(value)));
+            }
+        }
+
+        #[test]
+        fn prop_apply(map in map(), starts in vec((-60i64..60, 0i64..20), 0..5)) {
+            let set = starts
+                .into_iter()
+                .map(|(start, len)| Interval::with_len(start, len))
+                .collect::<IntervalSet>();
+            let image = map.apply(&set);
+            let expected = (-100..100)
+                .filter(|&value| set.contains(value))
+                .map(|value| Interval::with_len(map.get(value), 1))
+                .collect::<IntervalSet>();
+            prop_assert_eq!(image, expected);
+        }
+    }
+}
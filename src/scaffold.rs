@@ -0,0 +1,337 @@
+use std::{
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::input;
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    /// There is no `src/aoc_<year>` module to add the day to.
+    NoYear {
+        year: u16,
+        path: PathBuf,
+    },
+    Exists {
+        path: PathBuf,
+    },
+    /// The year's `mod.rs` does not look like the generator expects.
+    Malformed {
+        path: PathBuf,
+        reason: String,
+    },
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::NoYear { year, path } => {
+                write!(f, "no module for {year} at {}", path.display())
+            }
+            ScaffoldError::Exists { path } => write!(f, "{} already exists", path.display()),
+            ScaffoldError::Malformed { path, reason } => {
+                write!(f, "{}: {reason}", path.display())
+            }
+            ScaffoldError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+        }
+    }
+}
+
+impl Error for ScaffoldError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ScaffoldError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// The nearest directory from `start` upwards with a `Cargo.toml`.
+pub fn crate_root(start: &Path) -> Option<&Path> {
+    start
+        .ancestors()
+        .find(|dir| dir.join("Cargo.toml").is_file())
+}
+
+/// Adds `day` to `year` under the crate at `root`: writes the day module, registers it in
+/// the year's `mod.rs` and creates an empty input under `data_dir`. Returns the files touched.
+pub fn new_day(
+    root: &Path,
+    data_dir: &Path,
+    year: u16,
+    day: u8,
+) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let year_dir = root.join("src").join(format!("aoc_{year}"));
+    let mod_path = year_dir.join("mod.rs");
+    let day_path = year_dir.join(format!("day_{day}.rs"));
+    if !mod_path.is_file() {
+        return Err(ScaffoldError::NoYear {
+            year,
+            path: year_dir,
+        });
+    }
+    if day_path.exists() {
+        return Err(ScaffoldError::Exists { path: day_path });
+    }
+
+    let year_mod = read(&mod_path)?;
+    let year_mod = register(&year_mod, year, day).map_err(|reason| ScaffoldError::Malformed {
+        path: mod_path.clone(),
+        reason,
+    })?;
+    write(&day_path, &day_module(year, day))?;
+    write(&mod_path, &year_mod)?;
+    let mut touched = vec![day_path, mod_path];
+
+    let input_path = input::input_path(data_dir, year, day);
+    if !input_path.exists() {
+        let dir = input::year_dir(data_dir, year);
+        fs::create_dir_all(&dir).map_err(|source| ScaffoldError::Io { path: dir, source })?;
+        write(&input_path, "")?;
+        touched.push(input_path);
+    }
+    Ok(touched)
+}
+
+fn read(path: &Path) -> Result<String, ScaffoldError> {
+    fs::read_to_string(path).map_err(|source| ScaffoldError::Io {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), ScaffoldError> {
+    fs::write(path, contents).map_err(|source| ScaffoldError::Io {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+/// Adds the `pub mod` declaration and the registry entry for `day`, keeping both in day order.
+pub fn register(year_mod: &str, year: u16, day: u8) -> Result<String, String> {
+    let mut lines = year_mod.lines().map(str::to_owned).collect::<Vec<_>>();
+    let mod_day = |line: &str| {
+        line.strip_prefix("pub mod day_")?
+            .strip_suffix(';')?
+            .parse::<u8>()
+            .ok()
+    };
+    let entry_day = |line: &str| {
+        line.trim()
+            .strip_prefix("Puzzle::new::<day_")?
+            .split_once(':')?
+            .0
+            .parse::<u8>()
+            .ok()
+    };
+    if lines.iter().any(|line| mod_day(line) == Some(day)) {
+        return Err(format!("day {day} is already registered"));
+    }
+    insert(&mut lines, mod_day, day, format!("pub mod day_{day};"))
+        .ok_or("no `pub mod day_<n>;` declarations")?;
+    insert(
+        &mut lines,
+        entry_day,
+        day,
+        format!("    Puzzle::new::<day_{day}::Day{day}>({year}, {day}),"),
+    )
+    .ok_or("no `Puzzle::new` registry entries")?;
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Inserts `line` among the run of lines `key` recognises, before the first with a larger key.
+/// `None` if no line is recognised.
+fn insert(
+    lines: &mut Vec<String>,
+    key: impl Fn(&str) -> Option<u8>,
+    day: u8,
+    line: String,
+) -> Option<()> {
+    let keyed = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| Some((idx, key(line)?)))
+        .collect::<Vec<_>>();
+    let (last, _) = *keyed.last()?;
+    let idx = keyed
+        .iter()
+        .find(|(_, other)| *other > day)
+        .map_or(last + 1, |(idx, _)| *idx);
+    lines.insert(idx, line);
+    Some(())
+}
+
+pub fn day_module(year: u16, day: u8) -> String {
+    format!(
+        r#"use crate::{{AocError, ParseError, Part, PuzzleId, Solution}};
+
+const ID: PuzzleId = PuzzleId::new({year}, {day});
+
+pub struct Day{day};
+
+impl Solution for Day{day} {{
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(_data: &str) -> Result<Self, ParseError> {{
+        Ok(Self)
+    }}
+
+    fn part_1(&self) -> Result<u32, AocError> {{
+        Err(ID.unsolvable(Part::One, "not solved yet"))
+    }}
+
+    fn part_2(&self) -> Result<u32, AocError> {{
+        Err(ID.unsolvable(Part::Two, "not solved yet"))
+    }}
+}}
+
+pub fn day_{day}_part_1(data: &str) -> Result<u32, AocError> {{
+    Day{day}::parse(data)?.part_1()
+}}
+
+pub fn day_{day}_part_2(data: &str) -> Result<u32, AocError> {{
+    Day{day}::parse(data)?.part_2()
+}}
+
+#[cfg(test)]
+mod test {{
+    use crate::input::load_or_skip;
+
+    use super::{{day_{day}_part_1, day_{day}_part_2}};
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn test_day_{day}_part_1() {{
+        let Some(data) = load_or_skip({year}, {day}) else {{
+            return;
+        }};
+
+        let solution = day_{day}_part_1(&data).unwrap();
+        println!("{year}.{day}.1: {{solution}}");
+    }}
+
+    #[test]
+    fn test_day_{day}_part_2() {{
+        let Some(data) = load_or_skip({year}, {day}) else {{
+            return;
+        }};
+
+        let solution = day_{day}_part_2(&data).unwrap();
+        println!("{year}.{day}.2: {{solution}}");
+    }}
+
+    #[test]
+    #[ignore = "fill in `EXAMPLE` and its answer"]
+    fn test_day_{day}_part_1_example() {{
+        assert_eq!(day_{day}_part_1(EXAMPLE).unwrap(), 0);
+    }}
+
+    #[test]
+    #[ignore = "fill in `EXAMPLE` and its answer"]
+    fn test_day_{day}_part_2_example() {{
+        assert_eq!(day_{day}_part_2(EXAMPLE).unwrap(), 0);
+    }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use super::{crate_root, new_day, register, ScaffoldError};
+
+    const YEAR_MOD: &str = "use crate::solution::Puzzle;
+
+pub mod day_1;
+pub mod day_3;
+
+pub(crate) const PUZZLES: &[Puzzle] = &[
+    Puzzle::new::<day_1::Day1>(2023, 1),
+    Puzzle::new::<day_3::Day3>(2023, 3),
+];
+";
+
+    #[test]
+    fn test_register() {
+        assert_eq!(
+            register(YEAR_MOD, 2023, 2).unwrap(),
+            "use crate::solution::Puzzle;
+
+pub mod day_1;
+pub mod day_2;
+pub mod day_3;
+
+pub(crate) const PUZZLES: &[Puzzle] = &[
+    Puzzle::new::<day_1::Day1>(2023, 1),
+    Puzzle::new::<day_2::Day2>(2023, 2),
+    Puzzle::new::<day_3::Day3>(2023, 3),
+];
+"
+        );
+        let appended = register(YEAR_MOD, 2023, 10).unwrap();
+        assert!(appended.contains("pub mod day_3;\npub mod day_10;\n"));
+        assert!(appended.contains("(2023, 3),\n    Puzzle::new::<day_10::Day10>(2023, 10),\n];"));
+        assert!(register(YEAR_MOD, 2023, 3).is_err());
+        assert!(register("", 2023, 1).is_err());
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = env::temp_dir().join("aoc-scaffold-test");
+        let _ = fs::remove_dir_all(&root);
+        let data_dir = root.join("data");
+        fs::create_dir_all(root.join("src/aoc_2023")).unwrap();
+        fs::write(root.join("src/aoc_2023/mod.rs"), YEAR_MOD).unwrap();
+
+        let touched = new_day(&root, &data_dir, 2023, 2).unwrap();
+        assert_eq!(
+            touched,
+            [
+                root.join("src/aoc_2023/day_2.rs"),
+                root.join("src/aoc_2023/mod.rs"),
+                data_dir.join("aoc_2023/day_2.txt"),
+            ]
+        );
+        let module = fs::read_to_string(&touched[0]).unwrap();
+        assert!(module.contains("const ID: PuzzleId = PuzzleId::new(2023, 2);"));
+        assert!(module.contains(
+            "#[ignore = \"fill in `EXAMPLE` and its answer\"]\n    fn test_day_2_part_2_example()"
+        ));
+        assert!(fs::read_to_string(&touched[1])
+            .unwrap()
+            .contains("pub mod day_2;"));
+        assert_eq!(fs::read_to_string(&touched[2]).unwrap(), "");
+
+        assert!(matches!(
+            new_day(&root, &data_dir, 2023, 2),
+            Err(ScaffoldError::Exists { .. })
+        ));
+        assert!(matches!(
+            new_day(&root, &data_dir, 2022, 1),
+            Err(ScaffoldError::NoYear { year: 2022, .. })
+        ));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_crate_root() {
+        let root = env::temp_dir().join("aoc-crate-root-test");
+        let _ = fs::remove_dir_all(&root);
+        let nested = root.join("src/aoc_2023");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.join("Cargo.toml"), "").unwrap();
+
+        assert_eq!(crate_root(&nested), Some(root.as_path()));
+        assert_eq!(crate_root(&root), Some(root.as_path()));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
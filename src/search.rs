@@ -0,0 +1,368 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    error::Error,
+    fmt,
+    hash::Hash,
+    ops::Add,
+};
+
+/// Edge weights and path lengths. `Default` must be zero.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<C: Copy + Ord + Add<Output = C> + Default> Cost for C {}
+
+/// The cheapest way found to a goal: `path` runs from the start to the goal, both included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Found<S, C> {
+    pub cost: C,
+    pub path: Vec<S>,
+}
+
+impl<S, C> Found<S, C> {
+    pub fn goal(&self) -> &S {
+        self.path.last().expect("a path includes its start")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchError {
+    /// Every reachable state was explored without finding a goal.
+    Unreachable { explored: usize },
+    /// More states were discovered than the search was allowed to hold.
+    LimitExceeded { limit: usize },
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::Unreachable { explored } => {
+                write!(f, "no goal among {explored} reachable states")
+            }
+            SearchError::LimitExceeded { limit } => {
+                write!(f, "gave up after discovering {limit} states")
+            }
+        }
+    }
+}
+
+impl Error for SearchError {}
+
+/// Every state discovered so far, with how it was reached.
+struct Explored<S, C> {
+    states: Vec<S>,
+    /// Index of the state each one was reached from; the start points at itself.
+    parents: Vec<usize>,
+    costs: Vec<C>,
+    index: HashMap<S, usize>,
+    limit: usize,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> Explored<S, C> {
+    fn new(start: S, limit: usize) -> Self {
+        Self {
+            states: vec![start.clone()],
+            parents: vec![0],
+            costs: vec![C::default()],
+            index: HashMap::from([(start, 0)]),
+            limit,
+        }
+    }
+
+    /// Records reaching `state` from `parent` at `cost`, returning its index if that is the
+    /// first or cheapest way there so far.
+    fn reach(&mut self, state: S, parent: usize, cost: C) -> Result<Option<usize>, SearchError> {
+        match self.index.entry(state) {
+            Entry::Occupied(entry) => {
+                let idx = *entry.get();
+                if cost < self.costs[idx] {
+                    self.costs[idx] = cost;
+                    self.parents[idx] = parent;
+                    Ok(Some(idx))
+                } else {
+                    Ok(None)
+                }
+            }
+            Entry::Vacant(entry) => {
+                if self.states.len() >= self.limit {
+                    return Err(SearchError::LimitExceeded { limit: self.limit });
+                }
+                let idx = self.states.len();
+                self.states.push(entry.key().clone());
+                entry.insert(idx);
+                self.parents.push(parent);
+                self.costs.push(cost);
+                Ok(Some(idx))
+            }
+        }
+    }
+
+    fn found(self, goal: usize) -> Found<S, C> {
+        let cost = self.costs[goal];
+        let mut indices = vec![goal];
+        let mut idx = goal;
+        while self.parents[idx] != idx {
+            idx = self.parents[idx];
+            indices.push(idx);
+        }
+        let path = indices
+            .into_iter()
+            .rev()
+            .map(|idx| self.states[idx].clone())
+            .collect();
+        Found { cost, path }
+    }
+}
+
+/// Search settings. The default has no limit on the number of states.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Search {
+    limit: usize,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self { limit: usize::MAX }
+    }
+}
+
+impl Search {
+    /// Gives up once `limit` distinct states have been discovered.
+    pub fn with_limit(limit: usize) -> Self {
+        Self { limit }
+    }
+
+    /// The path with the fewest steps from `start` to a state satisfying `goal`.
+    pub fn bfs<S, I>(
+        &self,
+        start: S,
+        mut successors: impl FnMut(&S) -> I,
+        mut goal: impl FnMut(&S) -> bool,
+    ) -> Result<Found<S, usize>, SearchError>
+    where
+        S: Clone + Eq + Hash,
+        I: IntoIterator<Item = S>,
+    {
+        let mut explored = Explored::new(start, self.limit);
+        let mut queue = VecDeque::from([0]);
+        while let Some(idx) = queue.pop_front() {
+            if goal(&explored.states[idx]) {
+                return Ok(explored.found(idx));
+            }
+            let cost = explored.costs[idx] + 1;
+            for next in successors(&explored.states[idx]) {
+                // Breadth first reaches every state at its lowest cost the first time.
+                if !explored.index.contains_key(&next) {
+                    queue.extend(explored.reach(next, idx, cost)?);
+                }
+            }
+        }
+        Err(SearchError::Unreachable {
+            explored: explored.states.len(),
+        })
+    }
+
+    /// The cheapest path from `start` to a state satisfying `goal`, where `successors` gives
+    /// each next state with the non-negative cost of stepping to it.
+    pub fn dijkstra<S, C, I>(
+        &self,
+        start: S,
+        successors: impl FnMut(&S) -> I,
+        goal: impl FnMut(&S) -> bool,
+    ) -> Result<Found<S, C>, SearchError>
+    where
+        S: Clone + Eq + Hash,
+        C: Cost,
+        I: IntoIterator<Item = (S, C)>,
+    {
+        self.astar(start, successors, |_| C::default(), goal)
+    }
+
+    /// Like [`dijkstra`](Self::dijkstra), guided by `heuristic`, which must never overestimate
+    /// the remaining cost to a goal for the result to be the cheapest.
+    pub fn astar<S, C, I>(
+        &self,
+        start: S,
+        mut successors: impl FnMut(&S) -> I,
+        mut heuristic: impl FnMut(&S) -> C,
+        mut goal: impl FnMut(&S) -> bool,
+    ) -> Result<Found<S, C>, SearchError>
+    where
+        S: Clone + Eq + Hash,
+        C: Cost,
+        I: IntoIterator<Item = (S, C)>,
+    {
+        let mut queue = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+        let mut explored = Explored::new(start, self.limit);
+        while let Some(Reverse((_, cost, idx))) = queue.pop() {
+            // A cheaper way here was queued after this one and has been expanded already.
+            if cost > explored.costs[idx] {
+                continue;
+            }
+            if goal(&explored.states[idx]) {
+                return Ok(explored.found(idx));
+            }
+            for (next, step) in successors(&explored.states[idx]) {
+                let estimate = heuristic(&next);
+                let next_cost = cost + step;
+                if let Some(next) = explored.reach(next, idx, next_cost)? {
+                    queue.push(Reverse((next_cost + estimate, next_cost, next)));
+                }
+            }
+        }
+        Err(SearchError::Unreachable {
+            explored: explored.states.len(),
+        })
+    }
+}
+
+/// [`Search::bfs`] without a state limit.
+pub fn bfs<S, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Result<Found<S, usize>, SearchError>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    Search::default().bfs(start, successors, goal)
+}
+
+/// [`Search::dijkstra`] without a state limit.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Result<Found<S, C>, SearchError>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    Search::default().dijkstra(start, successors, goal)
+}
+
+/// [`Search::astar`] without a state limit.
+pub fn astar<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    goal: impl FnMut(&S) -> bool,
+) -> Result<Found<S, C>, SearchError>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    Search::default().astar(start, successors, heuristic, goal)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        geometry::Point2,
+        grid::Grid,
+        search::{astar, bfs, dijkstra, Search, SearchError},
+        PuzzleId,
+    };
+
+    const ID: PuzzleId = PuzzleId::new(2023, 0);
+
+    const MAZE: &str = "\
+..#....
+.##.##.
+...#...
+.#...#.
+...#...";
+
+    const RISKS: &str = "\
+1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581";
+
+    fn maze() -> Grid<bool> {
+        Grid::parse(ID, MAZE, |c| match c {
+            '.' => Ok(true),
+            '#' => Ok(false),
+            _ => Err("expected `.` or `#`"),
+        })
+        .unwrap()
+    }
+
+    fn risks() -> Grid<u32> {
+        Grid::parse(ID, RISKS, |c| c.to_digit(10).ok_or("expected a digit")).unwrap()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = maze();
+        let goal = Point2::new(6, 0);
+        let open = |p: &Point2| {
+            maze.neighbours4(*p)
+                .filter(|&n| maze[n])
+                .collect::<Vec<_>>()
+        };
+        let found = bfs(Point2::new(0, 0), open, |p| *p == goal).unwrap();
+        assert_eq!(found.cost, 12);
+        assert_eq!(found.path.len(), 13);
+        assert_eq!((found.path[0], *found.goal()), (Point2::new(0, 0), goal));
+        assert!(found
+            .path
+            .windows(2)
+            .all(|pair| pair[0].manhattan(pair[1]) == 1 && maze[pair[1]]));
+
+        let walled = |p: &Point2| {
+            maze.neighbours4(*p)
+                .filter(|&n| maze[n] && n.x < 3)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            bfs(Point2::new(0, 0), walled, |p| *p == goal),
+            Err(SearchError::Unreachable { explored: 11 })
+        );
+        assert_eq!(
+            Search::with_limit(5).bfs(Point2::new(0, 0), open, |p| *p == goal),
+            Err(SearchError::LimitExceeded { limit: 5 })
+        );
+    }
+
+    #[test]
+    fn test_weighted() {
+        let risks = risks();
+        let goal = Point2::new(9, 9);
+        let step = |p: &Point2| {
+            risks
+                .neighbours4(*p)
+                .map(|n| (n, risks[n]))
+                .collect::<Vec<_>>()
+        };
+        let found = dijkstra(Point2::new(0, 0), step, |p| *p == goal).unwrap();
+        assert_eq!(found.cost, 40);
+        assert_eq!(
+            found.path.iter().skip(1).map(|&p| risks[p]).sum::<u32>(),
+            40
+        );
+
+        let mut expanded = 0;
+        let guided = astar(
+            Point2::new(0, 0),
+            |p: &Point2| {
+                expanded += 1;
+                step(p)
+            },
+            |p| p.manhattan(goal) as u32,
+            |p| *p == goal,
+        )
+        .unwrap();
+        assert_eq!(guided.cost, 40);
+        assert!(expanded < 100);
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    fmt::{self, Display},
+    time::{Duration, Instant},
+};
+
+use rayon::ThreadPool;
+use serde::{Deserialize, Serialize};
+
+use crate::{aoc_2023, Answer, AocError, ParseError};
+
+/// Serialized as its number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "u8", try_from = "u8")]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl From<Part> for u8 {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            other => Err(other),
+        }
+    }
+}
+
+/// A puzzle solution: the input is parsed once and shared by both parts.
+pub trait Solution: Sized {
+    type Answer1: Into<Answer>;
+    type Answer2: Into<Answer>;
+
+    fn parse(data: &str) -> Result<Self, ParseError>;
+    fn part_1(&self) -> Result<Self::Answer1, AocError>;
+    fn part_2(&self) -> Result<Self::Answer2, AocError>;
+}
+
+/// A registry entry binding a (year, day) to its [`Solution`].
+#[derive(Clone, Copy)]
+pub struct Puzzle {
+    pub year: u16,
+    pub day: u8,
+    runner: fn(&str, Part) -> Result<Timed, AocError>,
+}
+
+/// An answer with the time spent parsing the input and solving the part.
+#[derive(Clone, Debug)]
+pub struct Timed {
+    pub answer: Answer,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl Puzzle {
+    pub const fn new<S: Solution>(year: u16, day: u8) -> Self {
+        Self {
+            year,
+            day,
+            runner: run::<S>,
+        }
+    }
+
+    pub fn run(&self, data: &str, part: Part) -> Result<Answer, AocError> {
+        self.timed(data, part).map(|timed| timed.answer)
+    }
+
+    /// Runs on a caller-supplied pool instead of rayon's global one.
+    pub fn run_in(&self, pool: &ThreadPool, data: &str, part: Part) -> Result<Answer, AocError> {
+        pool.install(|| self.run(data, part))
+    }
+
+    pub fn timed(&self, data: &str, part: Part) -> Result<Timed, AocError> {
+        (self.runner)(data, part)
+    }
+}
+
+fn run<S: Solution>(data: &str, part: Part) -> Result<Timed, AocError> {
+    let start = Instant::now();
+    let solution = S::parse(data)?;
+    let parsed = Instant::now();
+    let answer = match part {
+        Part::One => solution.part_1()?.into(),
+        Part::Two => solution.part_2()?.into(),
+    };
+    Ok(Timed {
+        answer,
+        parse: parsed - start,
+        solve: parsed.elapsed(),
+    })
+}
+
+pub fn puzzles() -> impl Iterator<Item = &'static Puzzle> {
+    aoc_2023::PUZZLES.iter()
+}
+
+pub fn find(year: u16, day: u8) -> Option<&'static Puzzle> {
+    puzzles().find(|puzzle| puzzle.year == year && puzzle.day == day)
+}
+
+pub fn solve(year: u16, day: u8, part: Part, input: &str) -> Result<Answer, AocError> {
+    find(year, day)
+        .ok_or(AocError::UnknownPuzzle { year, day })?
+        .run(input, part)
+}
+
+pub fn solve_in(
+    pool: &ThreadPool,
+    year: u16,
+    day: u8,
+    part: Part,
+    input: &str,
+) -> Result<Answer, AocError> {
+    pool.install(|| solve(year, day, part, input))
+}
+
+#[cfg(test)]
+mod test {
+    use rayon::ThreadPoolBuilder;
+
+    use crate::AocError;
+
+    use super::{find, puzzles, solve, solve_in, Part};
+
+    #[test]
+    fn test_registry_is_sorted_and_unique() {
+        let keys = puzzles()
+            .map(|puzzle| (puzzle.year, puzzle.day))
+            .collect::<Vec<_>>();
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_unknown_puzzle() {
+        assert!(find(2023, 26).is_none());
+        assert!(matches!(
+            solve(2015, 1, Part::One, ""),
+            Err(AocError::UnknownPuzzle { year: 2015, day: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_solve_in_pool() {
+        let pool = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+        let data = "Time:      7  15   30\nDistance:  9  40  200\n";
+        assert_eq!(
+            solve_in(&pool, 2023, 6, Part::One, data).unwrap(),
+            288.into()
+        );
+        assert_eq!(
+            find(2023, 6)
+                .unwrap()
+                .run_in(&pool, data, Part::Two)
+                .unwrap(),
+            71503.into()
+        );
+    }
+
+    #[test]
+    fn test_part_from_number() {
+        assert_eq!(Part::try_from(1), Ok(Part::One));
+        assert_eq!(Part::try_from(2), Ok(Part::Two));
+        assert_eq!(Part::try_from(3), Err(3));
+    }
+}
@@ -0,0 +1,311 @@
+use std::{error::Error, fmt, time::Duration};
+
+use crate::{
+    client::{Client, ClientError},
+    history::{Conflict, Feedback, History},
+    input::InputError,
+    Answer, Part,
+};
+
+/// The site's reply to a submitted answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Response {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    /// An answer was submitted too recently.
+    Wait(Duration),
+    /// The part is already solved, or not unlocked yet.
+    AlreadySolved,
+    /// The text of a reply we do not recognise.
+    Unrecognised(String),
+}
+
+impl Response {
+    /// Replies that say whether the answer was right, as opposed to not judging it.
+    pub fn feedback(&self) -> Option<Feedback> {
+        match self {
+            Response::Correct => Some(Feedback::Correct),
+            Response::TooHigh => Some(Feedback::TooHigh),
+            Response::TooLow => Some(Feedback::TooLow),
+            Response::Incorrect => Some(Feedback::Incorrect),
+            Response::Wait(_) | Response::AlreadySolved | Response::Unrecognised(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Response::Correct => write!(f, "correct"),
+            Response::TooHigh => write!(f, "wrong, too high"),
+            Response::TooLow => write!(f, "wrong, too low"),
+            Response::Incorrect => write!(f, "wrong"),
+            Response::Wait(wait) => write!(f, "submitted too recently, wait {}s", wait.as_secs()),
+            Response::AlreadySolved => write!(f, "already solved"),
+            Response::Unrecognised(text) => write!(f, "unrecognised response: {text}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    Client(ClientError),
+    History(InputError),
+    /// The history says the answer is wrong, so it was not sent.
+    Conflict {
+        answer: Answer,
+        conflict: Conflict,
+    },
+    /// The part was already solved with this answer.
+    Solved {
+        answer: Answer,
+    },
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Client(err) => err.fmt(f),
+            SubmitError::History(err) => err.fmt(f),
+            SubmitError::Conflict { answer, conflict } => write!(f, "{answer} {conflict}"),
+            SubmitError::Solved { answer } => write!(f, "{answer} was already accepted"),
+        }
+    }
+}
+
+impl Error for SubmitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SubmitError::Client(err) => Some(err),
+            SubmitError::History(err) => Some(err),
+            SubmitError::Conflict { .. } | SubmitError::Solved { .. } => None,
+        }
+    }
+}
+
+impl From<ClientError> for SubmitError {
+    fn from(err: ClientError) -> Self {
+        SubmitError::Client(err)
+    }
+}
+
+impl From<InputError> for SubmitError {
+    fn from(err: InputError) -> Self {
+        SubmitError::History(err)
+    }
+}
+
+pub fn answer_url(year: u16, day: u8) -> String {
+    format!("/{year}/day/{day}/answer")
+}
+
+/// Posts `answer` unless `history` shows it is wrong or the part is solved, then records and
+/// saves the verdict.
+pub fn submit(
+    client: &Client,
+    history: &mut History,
+    day: u8,
+    part: Part,
+    answer: &Answer,
+) -> Result<Response, SubmitError> {
+    if let Some(conflict) = history.check(day, part, answer) {
+        return Err(SubmitError::Conflict {
+            answer: answer.clone(),
+            conflict,
+        });
+    }
+    if history.correct(day, part).is_some() {
+        return Err(SubmitError::Solved {
+            answer: answer.clone(),
+        });
+    }
+    let html = client.post_form(
+        &answer_url(history.year(), day),
+        &[
+            ("level", &part.to_string()),
+            ("answer", &answer.to_string()),
+        ],
+    )?;
+    let response = parse_response(&html);
+    if let Some(feedback) = response.feedback() {
+        history.record(day, part, answer.clone(), feedback);
+        history.save()?;
+    }
+    Ok(response)
+}
+
+/// Reads the verdict out of the `<article>` of an answer page.
+pub fn parse_response(html: &str) -> Response {
+    let article = html
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(html, |(article, _)| article);
+    let text = strip_tags(article);
+    if text.contains("That's the right answer") {
+        Response::Correct
+    } else if text.contains("your answer is too high") {
+        Response::TooHigh
+    } else if text.contains("your answer is too low") {
+        Response::TooLow
+    } else if text.contains("That's not the right answer") {
+        Response::Incorrect
+    } else if text.contains("You gave an answer too recently") {
+        Response::Wait(parse_wait(&text).unwrap_or_default())
+    } else if text.contains("You don't seem to be solving the right level") {
+        Response::AlreadySolved
+    } else {
+        Response::Unrecognised(text)
+    }
+}
+
+/// Drops tags and collapses whitespace.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The `4m 57s` in "You have 4m 57s left to wait".
+fn parse_wait(text: &str) -> Option<Duration> {
+    let (_, rest) = text.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+    wait.split_whitespace()
+        .try_fold(Duration::ZERO, |total, amount| {
+            let unit = match amount.chars().last()? {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+            let value: u64 = amount[..amount.len() - 1].parse().ok()?;
+            Some(total + Duration::from_secs(value * unit))
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, time::Duration};
+
+    use crate::{
+        client::{mock, Client},
+        history::{Conflict, Feedback, History},
+        Answer, Part,
+    };
+
+    use super::{parse_response, submit, Response, SubmitError};
+
+    fn page(article: &str) -> String {
+        format!("<html><body><main>\n<article><p>{article}</p></article>\n</main></body></html>")
+    }
+
+    #[test]
+    fn test_parse_response() {
+        for (article, response) in [
+            (
+                "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer.",
+                Response::Correct,
+            ),
+            (
+                "That's not the right answer; your answer is too high.  Please wait one minute before trying again.",
+                Response::TooHigh,
+            ),
+            (
+                "That's not the right answer; your answer is too low.",
+                Response::TooLow,
+            ),
+            (
+                "That's not the right answer.  If you're stuck, make sure you're using the full input data.",
+                Response::Incorrect,
+            ),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 57s left to wait.",
+                Response::Wait(Duration::from_secs(297)),
+            ),
+            (
+                "You don't seem to be solving the right level.  Did you already complete it? <a href=\"/2023/day/5\">[Return to Day 5]</a>",
+                Response::AlreadySolved,
+            ),
+            (
+                "Something <em>new</em>.",
+                Response::Unrecognised("Something new.".to_owned()),
+            ),
+        ] {
+            assert_eq!(parse_response(&page(article)), response);
+        }
+    }
+
+    #[test]
+    fn test_submit_records_history() {
+        let data_dir = env::temp_dir().join("aoc-submit-test-history");
+        let _ = fs::remove_dir_all(&data_dir);
+        let (base_url, server) = mock::serve(vec![
+            mock::response(
+                200,
+                &[],
+                &page("That's not the right answer; your answer is too high."),
+            ),
+            mock::response(
+                200,
+                &[],
+                &page("You gave an answer too recently; You have 30s left to wait."),
+            ),
+            mock::response(200, &[], &page("That's the right answer!")),
+        ]);
+        let client = Client::new(&base_url, "secret").with_interval(Duration::ZERO);
+        let mut history = History::load(&data_dir, 2023).unwrap();
+
+        let wrong = Answer::from(40);
+        assert_eq!(
+            submit(&client, &mut history, 5, Part::One, &wrong).unwrap(),
+            Response::TooHigh
+        );
+        assert!(matches!(
+            submit(&client, &mut history, 5, Part::One, &wrong),
+            Err(SubmitError::Conflict {
+                conflict: Conflict::Rejected(Feedback::TooHigh),
+                ..
+            })
+        ));
+        assert!(matches!(
+            submit(&client, &mut history, 5, Part::One, &Answer::from(41)),
+            Err(SubmitError::Conflict {
+                conflict: Conflict::TooHigh { .. },
+                ..
+            })
+        ));
+        let right = Answer::from(35);
+        assert_eq!(
+            submit(&client, &mut history, 5, Part::One, &right).unwrap(),
+            Response::Wait(Duration::from_secs(30))
+        );
+        assert_eq!(
+            submit(&client, &mut history, 5, Part::One, &right).unwrap(),
+            Response::Correct
+        );
+        assert!(matches!(
+            submit(&client, &mut history, 5, Part::One, &right),
+            Err(SubmitError::Solved { .. })
+        ));
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("POST /2023/day/5/answer "));
+        assert!(requests[0].ends_with("level=1&answer=40"));
+
+        let history = History::load(&data_dir, 2023).unwrap();
+        assert_eq!(history.guesses(5, Part::One).count(), 2);
+        assert_eq!(history.correct(5, Part::One), Some(&right));
+        fs::remove_dir_all(data_dir).unwrap();
+    }
+}
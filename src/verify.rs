@@ -0,0 +1,245 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use toml::{Table, Value};
+
+use crate::{
+    input::{self, InputError, Source},
+    puzzles, Answer, AocError, Part, Puzzle,
+};
+
+/// Known answers for one year, read from `<data dir>/aoc_<year>/answers.toml`:
+///
+/// ```toml
+/// [day_1]
+/// part_1 = 142
+/// part_2 = "281"
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: HashMap<(u8, Part), Answer>,
+}
+
+impl Answers {
+    pub fn path(data_dir: &Path, year: u16) -> PathBuf {
+        input::year_dir(data_dir, year).join("answers.toml")
+    }
+
+    /// `Ok(None)` when there is no answers file.
+    pub fn load(path: &Path) -> Result<Option<Self>, InputError> {
+        match fs::read_to_string(path) {
+            Ok(data) => Self::parse(path, &data).map(Some),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(InputError::Io {
+                path: Some(path.to_owned()),
+                source,
+            }),
+        }
+    }
+
+    pub fn parse(path: &Path, data: &str) -> Result<Self, InputError> {
+        let invalid = |reason: String| InputError::Invalid {
+            path: path.to_owned(),
+            reason,
+        };
+        let table = data
+            .parse::<Table>()
+            .map_err(|err| invalid(err.message().to_owned()))?;
+
+        let mut answers = HashMap::new();
+        for (key, parts) in table {
+            let day = key
+                .strip_prefix("day_")
+                .and_then(|day| day.parse::<u8>().ok())
+                .ok_or_else(|| invalid(format!("expected `[day_<n>]`, found `[{key}]`")))?;
+            let Value::Table(parts) = parts else {
+                return Err(invalid(format!("`{key}` is not a table")));
+            };
+            for (name, value) in parts {
+                let part = match name.as_str() {
+                    "part_1" => Part::One,
+                    "part_2" => Part::Two,
+                    _ => return Err(invalid(format!("unknown key `{key}.{name}`"))),
+                };
+                let answer = match value {
+                    Value::Integer(answer) => answer.into(),
+                    Value::String(answer) => answer.into(),
+                    _ => return Err(invalid(format!("`{key}.{name}` is not a number or string"))),
+                };
+                answers.insert((day, part), answer);
+            }
+        }
+        Ok(Self { answers })
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Correct,
+    Mismatch { expected: Answer, actual: Answer },
+    Failed(AocError),
+    NoInput,
+    NoAnswer,
+}
+
+impl Outcome {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Outcome::Mismatch { .. } | Outcome::Failed(_))
+    }
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub year: u16,
+    pub day: u8,
+    pub part: Part,
+    pub outcome: Outcome,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}: ", self.year, self.day, self.part)?;
+        match &self.outcome {
+            Outcome::Correct => write!(f, "ok"),
+            Outcome::Mismatch { expected, actual } => {
+                write!(f, "MISMATCH expected {expected}, got {actual}")
+            }
+            Outcome::Failed(err) => write!(f, "FAILED {err}"),
+            Outcome::NoInput => write!(f, "skipped, no input"),
+            Outcome::NoAnswer => write!(f, "skipped, no answer"),
+        }
+    }
+}
+
+pub fn check(puzzle: &Puzzle, data: &str, part: Part, expected: Option<&Answer>) -> Outcome {
+    let Some(expected) = expected else {
+        return Outcome::NoAnswer;
+    };
+    match puzzle.run(data, part) {
+        Ok(actual) if actual == *expected => Outcome::Correct,
+        Ok(actual) => Outcome::Mismatch {
+            expected: expected.clone(),
+            actual,
+        },
+        Err(err) => Outcome::Failed(err),
+    }
+}
+
+/// Unreadable (as opposed to missing) inputs are an error rather than a failed check.
+pub fn verify(puzzle: &Puzzle, answers: Option<&Answers>) -> Result<Vec<Check>, InputError> {
+    let data = match input::load(puzzle.year, puzzle.day, &Source::DataDir) {
+        Ok(data) => Some(data),
+        Err(InputError::Missing { .. }) => None,
+        Err(err) => return Err(err),
+    };
+    Ok(Part::ALL
+        .into_iter()
+        .map(|part| {
+            let expected = answers.and_then(|answers| answers.get(puzzle.day, part));
+            Check {
+                year: puzzle.year,
+                day: puzzle.day,
+                part,
+                outcome: match &data {
+                    Some(data) => check(puzzle, data, part, expected),
+                    None => Outcome::NoInput,
+                },
+            }
+        })
+        .collect())
+}
+
+/// Checks every registered puzzle against its year's answers file.
+pub fn verify_all() -> Result<Vec<Check>, InputError> {
+    let mut years: HashMap<u16, Option<Answers>> = HashMap::new();
+    let mut checks = Vec::new();
+    for puzzle in puzzles() {
+        let answers = match years.entry(puzzle.year) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Answers::load(&Answers::path(
+                &input::data_dir(),
+                puzzle.year,
+            ))?),
+        };
+        checks.extend(verify(puzzle, answers.as_ref())?);
+    }
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use crate::{find, input::InputError, Part};
+
+    use super::{check, verify_all, Answers, Outcome};
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = Answers::parse(
+            Path::new("answers.toml"),
+            "[day_1]\npart_1 = 142\npart_2 = \"281\"\n\n[day_6]\npart_2 = 71503\n",
+        )
+        .unwrap();
+        assert_eq!(answers.get(1, Part::One), Some(&142.into()));
+        assert_eq!(answers.get(1, Part::Two), Some(&281.into()));
+        assert_eq!(answers.get(6, Part::One), None);
+        assert_eq!(answers.get(6, Part::Two), Some(&71503.into()));
+    }
+
+    #[test]
+    fn test_parse_invalid_answers() {
+        for data in [
+            "[day_x]\npart_1 = 1\n",
+            "[day_1]\npart_3 = 1\n",
+            "day_1 = 1\n",
+        ] {
+            assert!(matches!(
+                Answers::parse(Path::new("answers.toml"), data),
+                Err(InputError::Invalid { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let puzzle = find(2023, 6).unwrap();
+        let data = "Time:      7  15   30\nDistance:  9  40  200\n";
+        assert!(matches!(
+            check(puzzle, data, Part::One, Some(&288.into())),
+            Outcome::Correct
+        ));
+        assert!(matches!(
+            check(puzzle, data, Part::Two, Some(&71504.into())),
+            Outcome::Mismatch { .. }
+        ));
+        assert!(matches!(
+            check(puzzle, data, Part::Two, None),
+            Outcome::NoAnswer
+        ));
+        assert!(matches!(
+            check(puzzle, "Time: 7", Part::One, Some(&4.into())),
+            Outcome::Failed(_)
+        ));
+    }
+
+    #[test]
+    fn test_registered_answers() {
+        let checks = verify_all().unwrap();
+        for check in &checks {
+            println!("{check}");
+        }
+        let failures = checks
+            .iter()
+            .filter(|check| check.outcome.is_failure())
+            .count();
+        assert_eq!(failures, 0, "{failures} of {} checks failed", checks.len());
+    }
+}